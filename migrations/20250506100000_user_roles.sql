@@ -0,0 +1,5 @@
+-- Роли пользователей
+CREATE TYPE user_role AS ENUM ('admin', 'editor', 'teacher', 'student');
+
+ALTER TABLE users
+    ADD COLUMN role user_role NOT NULL DEFAULT 'student';
@@ -0,0 +1,24 @@
+-- Refresh-токены и отзыв сессий
+CREATE TABLE refresh_tokens
+(
+    id          SERIAL PRIMARY KEY,
+    user_id     INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    token_hash  TEXT NOT NULL UNIQUE,
+    expires_at  TIMESTAMPTZ NOT NULL,
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    revoked_at  TIMESTAMPTZ,
+    replaced_by INTEGER REFERENCES refresh_tokens(id) ON DELETE SET NULL
+);
+
+CREATE INDEX refresh_tokens_user_id_idx ON refresh_tokens (user_id);
+
+-- Отозванные access-токены (по jti) до истечения их срока
+CREATE TABLE revoked_tokens
+(
+    jti        TEXT PRIMARY KEY,
+    expires_at TIMESTAMPTZ NOT NULL
+);
+
+-- Все access-токены, выданные раньше этого момента, считаются недействительными
+ALTER TABLE users
+    ADD COLUMN sessions_revoked_at TIMESTAMPTZ;
@@ -0,0 +1,16 @@
+-- Подтверждение email и сброс пароля
+ALTER TABLE users
+    ADD COLUMN email_verified_at TIMESTAMPTZ;
+
+CREATE TABLE email_tokens
+(
+    id         SERIAL PRIMARY KEY,
+    user_id    INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    token_hash TEXT NOT NULL UNIQUE,
+    purpose    TEXT NOT NULL CHECK (purpose IN ('verify_email', 'reset_password')),
+    expires_at TIMESTAMPTZ NOT NULL,
+    used_at    TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX email_tokens_user_id_idx ON email_tokens (user_id);
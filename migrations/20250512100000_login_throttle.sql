@@ -0,0 +1,21 @@
+-- Защита от перебора паролей
+CREATE TABLE login_throttle
+(
+    key             TEXT PRIMARY KEY,          -- 'user:<username>' или 'ip:<адрес>'
+    failures        INTEGER NOT NULL DEFAULT 0,
+    last_failure_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    locked_until    TIMESTAMPTZ
+);
+
+-- Журнал неудачных попыток входа
+CREATE TABLE failed_logins
+(
+    id         SERIAL PRIMARY KEY,
+    username   TEXT NOT NULL,
+    ip         TEXT NOT NULL,
+    reason     TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX failed_logins_username_idx ON failed_logins (username, created_at);
+CREATE INDEX failed_logins_ip_idx ON failed_logins (ip, created_at);
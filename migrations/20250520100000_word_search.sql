@@ -0,0 +1,20 @@
+-- Нормализация арабского текста для поиска без учёта огласовок.
+-- Правила совпадают с arabic::normalize в коде приложения:
+--   убираются харакаты, танвин, шадда, сукун, коранические знаки и татвиль;
+--   أ إ آ ٱ -> ا, ة -> ه, ى -> ي
+CREATE OR REPLACE FUNCTION arabic_normalize(input TEXT) RETURNS TEXT
+    LANGUAGE sql IMMUTABLE STRICT PARALLEL SAFE
+AS $$
+    SELECT translate(
+        regexp_replace(input, '[\u0610-\u061A\u064B-\u065F\u0670\u06D6-\u06ED\u0640]', '', 'g'),
+        'أإآٱةى',
+        'ااااهي'
+    );
+$$;
+
+ALTER TABLE word
+    ADD COLUMN term_normalized TEXT GENERATED ALWAYS AS (arabic_normalize(term)) STORED;
+
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+CREATE INDEX word_term_normalized_trgm_idx ON word USING GIN (term_normalized gin_trgm_ops);
@@ -0,0 +1,9 @@
+-- Полнотекстовый поиск по урокам. Конфигурация 'simple' без стемминга,
+-- арабский текст предварительно нормализуется arabic_normalize.
+ALTER TABLE lesson
+    ADD COLUMN search_vector tsvector GENERATED ALWAYS AS (
+        setweight(to_tsvector('simple'::regconfig, arabic_normalize(title)), 'A')
+        || setweight(to_tsvector('simple'::regconfig, arabic_normalize(text)), 'B')
+    ) STORED;
+
+CREATE INDEX lesson_search_vector_idx ON lesson USING GIN (search_vector);
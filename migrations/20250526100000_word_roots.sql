@@ -0,0 +1,6 @@
+-- Корень (например, ك-ت-ب) и морфологическая модель (вазн, например, فَاعِل) слова
+ALTER TABLE word
+    ADD COLUMN root    VARCHAR(20),
+    ADD COLUMN pattern VARCHAR(50);
+
+CREATE INDEX word_root_idx ON word (root);
@@ -0,0 +1,18 @@
+-- Грамматические сведения о слове: часть речи, род, формы двойственного
+-- и множественного числа (в том числе ломаного), для глаголов — формы прошедшего
+-- и настоящего времени и масдар
+CREATE TYPE part_of_speech AS ENUM ('noun', 'verb', 'adjective', 'adverb', 'pronoun', 'preposition', 'particle');
+CREATE TYPE grammatical_gender AS ENUM ('masculine', 'feminine');
+CREATE TYPE plural_type AS ENUM ('sound_masculine', 'sound_feminine', 'broken');
+
+ALTER TABLE word
+    ADD COLUMN part_of_speech part_of_speech,
+    ADD COLUMN gender         grammatical_gender,
+    ADD COLUMN dual           VARCHAR(100),
+    ADD COLUMN plural         VARCHAR(100),
+    ADD COLUMN plural_type    plural_type,
+    ADD COLUMN past           VARCHAR(100),
+    ADD COLUMN present        VARCHAR(100),
+    ADD COLUMN masdar         VARCHAR(100);
+
+CREATE INDEX word_part_of_speech_idx ON word (part_of_speech);
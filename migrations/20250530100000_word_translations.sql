@@ -0,0 +1,16 @@
+-- Переводы слова на разные языки вместо единственного поля definition
+CREATE TABLE word_translation
+(
+    id         SERIAL PRIMARY KEY,
+    word_id    INTEGER      NOT NULL REFERENCES word (id) ON DELETE CASCADE,
+    lang       VARCHAR(3)   NOT NULL,
+    definition VARCHAR(255) NOT NULL,
+    UNIQUE (word_id, lang)
+);
+
+-- Существующие определения писались для русскоязычных групп
+INSERT INTO word_translation (word_id, lang, definition)
+SELECT id, 'ru', definition
+FROM word;
+
+ALTER TABLE word DROP COLUMN definition;
@@ -0,0 +1,13 @@
+-- Примеры употребления слова. Пример можно привязать к уроку, в тексте которого он встречается
+CREATE TABLE word_example
+(
+    id          SERIAL PRIMARY KEY,
+    word_id     INTEGER   NOT NULL REFERENCES word (id) ON DELETE CASCADE,
+    text        TEXT      NOT NULL,
+    translation TEXT,
+    source      VARCHAR(255),
+    lesson_id   INTEGER REFERENCES lesson (id) ON DELETE SET NULL,
+    created_at  TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX word_example_word_id_idx ON word_example (word_id);
@@ -0,0 +1,13 @@
+-- Аудио с произношением: у слова или урока может быть один файл.
+-- Сам файл лежит в хранилище, здесь только его ключ и метаданные
+CREATE TABLE audio_file
+(
+    id           SERIAL PRIMARY KEY,
+    word_id      INTEGER UNIQUE REFERENCES word (id) ON DELETE CASCADE,
+    lesson_id    INTEGER UNIQUE REFERENCES lesson (id) ON DELETE CASCADE,
+    storage_key  VARCHAR(255) NOT NULL,
+    content_type VARCHAR(50)  NOT NULL,
+    size         BIGINT       NOT NULL,
+    created_at   TIMESTAMP    NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CHECK ((word_id IS NULL) <> (lesson_id IS NULL))
+);
@@ -0,0 +1,14 @@
+-- Порядок уроков внутри учебника. Существующие уроки нумеруются в порядке id
+ALTER TABLE lesson ADD COLUMN position INTEGER;
+
+UPDATE lesson
+SET position = numbered.position
+FROM (SELECT id, ROW_NUMBER() OVER (PARTITION BY textbook_id ORDER BY id) AS position FROM lesson) AS numbered
+WHERE lesson.id = numbered.id;
+
+ALTER TABLE lesson ALTER COLUMN position SET NOT NULL;
+
+-- Откладываемое ограничение проверяется в конце оператора,
+-- поэтому перестановка одним UPDATE не спотыкается о промежуточные дубликаты
+ALTER TABLE lesson
+    ADD CONSTRAINT lesson_textbook_position_key UNIQUE (textbook_id, position) DEFERRABLE INITIALLY IMMEDIATE;
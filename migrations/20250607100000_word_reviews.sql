@@ -0,0 +1,15 @@
+-- Интервальное повторение: состояние карточки слова для каждого пользователя (SM-2)
+CREATE TABLE word_review
+(
+    user_id          INTEGER          NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    word_id          INTEGER          NOT NULL REFERENCES word (id) ON DELETE CASCADE,
+    ease             DOUBLE PRECISION NOT NULL,
+    interval_days    INTEGER          NOT NULL,
+    repetitions      INTEGER          NOT NULL,
+    lapses           INTEGER          NOT NULL,
+    due_at           TIMESTAMPTZ      NOT NULL,
+    last_reviewed_at TIMESTAMPTZ      NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, word_id)
+);
+
+CREATE INDEX word_review_due_idx ON word_review (user_id, due_at);
@@ -0,0 +1,16 @@
+-- Прогресс пользователя по урокам: запись появляется, как только урок начат
+CREATE TABLE lesson_progress
+(
+    user_id            INTEGER          NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    lesson_id          INTEGER          NOT NULL REFERENCES lesson (id) ON DELETE CASCADE,
+    started_at         TIMESTAMPTZ      NOT NULL DEFAULT NOW(),
+    completed_at       TIMESTAMPTZ,
+    -- сколько секунд урок был открыт, суммарно за все сессии
+    time_spent_seconds INTEGER          NOT NULL DEFAULT 0 CHECK (time_spent_seconds >= 0),
+    -- место, где пользователь остановился (номер токена текста или секунда видео — решает клиент)
+    last_position      INTEGER,
+    -- результат последнего теста по уроку, от 0 до 1
+    quiz_score         DOUBLE PRECISION CHECK (quiz_score BETWEEN 0 AND 1),
+    updated_at         TIMESTAMPTZ      NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, lesson_id)
+);
@@ -0,0 +1,273 @@
+use serde::{Deserialize, Serialize};
+
+use crate::arabic::normalize::{fold_letter, segments, Segment};
+
+const SHADDA: char = '\u{0651}';
+const TATWEEL: char = '\u{0640}';
+
+/// Ошибка в букве стоит целый балл, ошибка в огласовке — половину.
+const LETTER_PENALTY: f64 = 1.0;
+const MARK_PENALTY: f64 = 0.5;
+
+/// Насколько строго сравнивать напечатанный ответ с правильным.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Strictness {
+    /// Только буквы; огласовки и подставка хамзы не важны
+    #[default]
+    IgnoreDiacritics,
+    /// Как `ignore_diacritics`, но шадда должна стоять там же, где в правильном ответе
+    Shadda,
+    /// Буквы и все огласовки должны совпасть дословно
+    Exact,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DifferenceKind {
+    MissingLetter,
+    ExtraLetter,
+    WrongLetter,
+    WrongMarks,
+}
+
+/// Расхождение ответа с правильным.
+#[derive(Debug, Clone, Serialize)]
+pub struct Difference {
+    pub kind: DifferenceKind,
+    /// Номер буквы в правильном ответе; для лишней буквы — номер буквы, перед которой она стоит
+    pub position: usize,
+    /// Буква или огласовки правильного ответа (пустая строка — огласовок нет)
+    pub expected: Option<String>,
+    pub given: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Grading {
+    pub correct: bool,
+    /// От 0 до 1
+    pub score: f64,
+    pub differences: Vec<Difference>,
+}
+
+/// Для нестрогого сравнения все формы хамзы и алифа приводятся к одной.
+fn letter_key(c: char, strictness: Strictness) -> char {
+    match (strictness, fold_letter(c)) {
+        (Strictness::Exact, _) => c,
+        (_, 'ؤ' | 'ئ') => 'ء',
+        (_, folded) => folded,
+    }
+}
+
+fn prepare(text: &str) -> Vec<Segment> {
+    // Лишние пробелы между словами не считаются ошибкой
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mut result = segments(&text);
+    for (_, marks) in result.iter_mut() {
+        marks.retain(|&m| m != TATWEEL);
+    }
+    result.retain(|(c, _)| *c != TATWEEL);
+    result
+}
+
+fn marks_to_compare(marks: &[char], strictness: Strictness) -> Option<String> {
+    match strictness {
+        Strictness::IgnoreDiacritics => None,
+        Strictness::Shadda => Some(marks.iter().filter(|&&m| m == SHADDA).collect()),
+        Strictness::Exact => Some(marks.iter().collect()),
+    }
+}
+
+enum Step {
+    Same(usize, usize),
+    Replace(usize, usize),
+    Delete(usize),
+    Insert(usize, usize),
+}
+
+/// Выравнивание букв по расстоянию Левенштейна: пары совпавших/заменённых букв,
+/// пропущенные и лишние буквы в порядке правильного ответа.
+fn align(expected: &[char], given: &[char]) -> Vec<Step> {
+    let (n, m) = (expected.len(), given.len());
+    let mut cost = vec![vec![0usize; m + 1]; n + 1];
+
+    for (i, row) in cost.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in cost[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=n {
+        for j in 1..=m {
+            let replace = cost[i - 1][j - 1] + usize::from(expected[i - 1] != given[j - 1]);
+            cost[i][j] = replace.min(cost[i - 1][j] + 1).min(cost[i][j - 1] + 1);
+        }
+    }
+
+    let mut steps = Vec::new();
+    let (mut i, mut j) = (n, m);
+    while i > 0 || j > 0 {
+        if i > 0 && j > 0 && cost[i][j] == cost[i - 1][j - 1] + usize::from(expected[i - 1] != given[j - 1]) {
+            i -= 1;
+            j -= 1;
+            steps.push(if expected[i] == given[j] { Step::Same(i, j) } else { Step::Replace(i, j) });
+        } else if i > 0 && cost[i][j] == cost[i - 1][j] + 1 {
+            i -= 1;
+            steps.push(Step::Delete(i));
+        } else {
+            j -= 1;
+            steps.push(Step::Insert(i, j));
+        }
+    }
+
+    steps.reverse();
+    steps
+}
+
+/// Сравнивает напечатанный ответ с правильным с заданной строгостью.
+/// Ответ верен, если расхождений нет; балл уменьшается за каждую ошибку в букве и огласовке.
+pub fn grade_answer(expected: &str, given: &str, strictness: Strictness) -> Grading {
+    let expected = prepare(expected);
+    let given = prepare(given);
+
+    let expected_keys: Vec<char> = expected.iter().map(|(c, _)| letter_key(*c, strictness)).collect();
+    let given_keys: Vec<char> = given.iter().map(|(c, _)| letter_key(*c, strictness)).collect();
+
+    let letter = |c: char| Some(c.to_string());
+    let mut differences = Vec::new();
+
+    for step in align(&expected_keys, &given_keys) {
+        match step {
+            Step::Same(i, j) => {
+                let (expected_marks, given_marks) =
+                    (marks_to_compare(&expected[i].1, strictness), marks_to_compare(&given[j].1, strictness));
+                if expected_marks != given_marks {
+                    differences.push(Difference {
+                        kind: DifferenceKind::WrongMarks,
+                        position: i,
+                        expected: expected_marks,
+                        given: given_marks,
+                    });
+                }
+            }
+            Step::Replace(i, j) => differences.push(Difference {
+                kind: DifferenceKind::WrongLetter,
+                position: i,
+                expected: letter(expected[i].0),
+                given: letter(given[j].0),
+            }),
+            Step::Delete(i) => differences.push(Difference {
+                kind: DifferenceKind::MissingLetter,
+                position: i,
+                expected: letter(expected[i].0),
+                given: None,
+            }),
+            Step::Insert(i, j) => differences.push(Difference {
+                kind: DifferenceKind::ExtraLetter,
+                position: i,
+                expected: None,
+                given: letter(given[j].0),
+            }),
+        }
+    }
+
+    let penalty: f64 = differences
+        .iter()
+        .map(|d| if d.kind == DifferenceKind::WrongMarks { MARK_PENALTY } else { LETTER_PENALTY })
+        .sum();
+    let letters = expected.iter().filter(|(c, _)| !c.is_whitespace()).count().max(1) as f64;
+
+    Grading {
+        correct: differences.is_empty(),
+        score: (1.0 - penalty / letters).clamp(0.0, 1.0),
+        differences,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(grading: &Grading) -> Vec<(DifferenceKind, usize)> {
+        grading.differences.iter().map(|d| (d.kind, d.position)).collect()
+    }
+
+    #[test]
+    fn ignore_diacritics_compares_letters_only() {
+        let grading = grade_answer("كَتَبَ", "كتب", Strictness::IgnoreDiacritics);
+        assert!(grading.correct);
+        assert_eq!(grading.score, 1.0);
+
+        assert!(grade_answer("كَتَبَ", "كُتُبٌ", Strictness::IgnoreDiacritics).correct);
+        assert!(grade_answer("مَدْرَسَة", "  مدرسـة ", Strictness::IgnoreDiacritics).correct);
+    }
+
+    #[test]
+    fn shadda_level_checks_only_shadda() {
+        assert!(grade_answer("مُدَرِّس", "مدرّس", Strictness::Shadda).correct);
+
+        let grading = grade_answer("مُدَرِّس", "مدرس", Strictness::Shadda);
+        assert!(!grading.correct);
+        assert_eq!(kinds(&grading), [(DifferenceKind::WrongMarks, 2)]);
+        assert_eq!(grading.differences[0].expected.as_deref(), Some("\u{0651}"));
+        assert_eq!(grading.differences[0].given.as_deref(), Some(""));
+
+        let grading = grade_answer("دَرَسَ", "دَرَّسَ", Strictness::Shadda);
+        assert_eq!(kinds(&grading), [(DifferenceKind::WrongMarks, 1)]);
+    }
+
+    #[test]
+    fn exact_level_checks_every_mark() {
+        assert!(grade_answer("كَتَبَ", "كَتَبَ", Strictness::Exact).correct);
+
+        let grading = grade_answer("كَتَبَ", "كُتُبٌ", Strictness::Exact);
+        assert_eq!(
+            kinds(&grading),
+            [(DifferenceKind::WrongMarks, 0), (DifferenceKind::WrongMarks, 1), (DifferenceKind::WrongMarks, 2)]
+        );
+        assert!((grading.score - 0.5).abs() < 1e-9);
+
+        // Без огласовок ответ в строгом режиме неверен
+        assert!(!grade_answer("كَتَبَ", "كتب", Strictness::Exact).correct);
+    }
+
+    #[test]
+    fn hamza_seat_matters_only_in_exact_mode() {
+        assert!(grade_answer("سُؤَال", "سءال", Strictness::IgnoreDiacritics).correct);
+        assert!(grade_answer("مَسْئُول", "مسؤول", Strictness::Shadda).correct);
+        assert!(grade_answer("أَكَلَ", "اكل", Strictness::IgnoreDiacritics).correct);
+
+        let grading = grade_answer("سؤال", "سئال", Strictness::Exact);
+        assert_eq!(kinds(&grading), [(DifferenceKind::WrongLetter, 1)]);
+        assert_eq!(grading.differences[0].expected.as_deref(), Some("ؤ"));
+        assert_eq!(grading.differences[0].given.as_deref(), Some("ئ"));
+    }
+
+    #[test]
+    fn reports_missing_letter_position() {
+        let grading = grade_answer("مدرسة", "مدسة", Strictness::IgnoreDiacritics);
+        assert_eq!(kinds(&grading), [(DifferenceKind::MissingLetter, 2)]);
+        assert_eq!(grading.differences[0].expected.as_deref(), Some("ر"));
+        assert!((grading.score - 0.8).abs() < 1e-9);
+    }
+
+    #[test]
+    fn reports_extra_letter_position() {
+        let grading = grade_answer("كتب", "كتاب", Strictness::IgnoreDiacritics);
+        assert_eq!(kinds(&grading), [(DifferenceKind::ExtraLetter, 2)]);
+        assert_eq!(grading.differences[0].given.as_deref(), Some("ا"));
+
+        let grading = grade_answer("كتب", "كتبت", Strictness::IgnoreDiacritics);
+        assert_eq!(kinds(&grading), [(DifferenceKind::ExtraLetter, 3)]);
+    }
+
+    #[test]
+    fn reports_wrong_letter_and_clamps_score() {
+        let grading = grade_answer("قلم", "كلم", Strictness::IgnoreDiacritics);
+        assert_eq!(kinds(&grading), [(DifferenceKind::WrongLetter, 0)]);
+
+        let grading = grade_answer("قلم", "بيت كبير", Strictness::IgnoreDiacritics);
+        assert!(!grading.correct);
+        assert_eq!(grading.score, 0.0);
+    }
+}
@@ -0,0 +1,34 @@
+//! Работа с арабским текстом.
+
+pub mod grading;
+pub mod normalize;
+pub mod root;
+pub mod stemmer;
+pub mod tokenize;
+pub mod transliterate;
+
+/// Символ относится к арабской письменности (основной блок, дополнения и формы представления).
+pub fn is_arabic_char(c: char) -> bool {
+    matches!(c,
+        '\u{0600}'..='\u{06FF}'
+        | '\u{0750}'..='\u{077F}'
+        | '\u{08A0}'..='\u{08FF}'
+        | '\u{FB50}'..='\u{FDFF}'
+        | '\u{FE70}'..='\u{FEFF}')
+}
+
+/// Текст написан арабским письмом: содержит хотя бы одну арабскую букву,
+/// а кроме арабских символов допускаются только пробелы.
+pub fn is_arabic_text(text: &str) -> bool {
+    text.chars().any(|c| is_arabic_char(c) && c.is_alphabetic())
+        && text.chars().all(|c| is_arabic_char(c) || c.is_whitespace())
+}
+
+/// Предложение на арабском: хотя бы одна арабская буква, кроме арабских символов
+/// допускаются пробелы, цифры и ASCII-пунктуация ("." , "!", кавычки), но не латиница.
+pub fn is_arabic_sentence(text: &str) -> bool {
+    text.chars().any(|c| is_arabic_char(c) && c.is_alphabetic())
+        && text
+            .chars()
+            .all(|c| is_arabic_char(c) || c.is_whitespace() || c.is_ascii_digit() || c.is_ascii_punctuation())
+}
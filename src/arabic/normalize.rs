@@ -0,0 +1,52 @@
+/// Огласовки и служебные знаки, которые не влияют на поиск:
+/// харакаты, танвин, шадда, сукун, надстрочный алиф, коранические знаки и татвиль.
+pub fn is_diacritic(c: char) -> bool {
+    matches!(c,
+        '\u{0610}'..='\u{061A}'
+        | '\u{064B}'..='\u{065F}'
+        | '\u{0670}'
+        | '\u{06D6}'..='\u{06ED}'
+        | '\u{0640}')
+}
+
+/// Приводит варианты букв к одной форме: алиф с хамзой/маддой -> ا, ة -> ه, ى -> ي.
+pub fn fold_letter(c: char) -> char {
+    match c {
+        'أ' | 'إ' | 'آ' | 'ٱ' => 'ا',
+        'ة' => 'ه',
+        'ى' => 'ي',
+        _ => c,
+    }
+}
+
+/// Буква вместе с огласовками, стоящими после неё (огласовки отсортированы).
+pub type Segment = (char, Vec<char>);
+
+/// Делит текст на буквы с огласовками. Огласовки в начале текста, без буквы, отбрасываются.
+pub fn segments(text: &str) -> Vec<Segment> {
+    let mut result: Vec<Segment> = Vec::new();
+
+    for c in text.chars() {
+        if is_diacritic(c) {
+            if let Some((_, marks)) = result.last_mut() {
+                marks.push(c);
+            }
+        } else {
+            result.push((c, Vec::new()));
+        }
+    }
+
+    for (_, marks) in result.iter_mut() {
+        marks.sort_unstable();
+    }
+    result
+}
+
+/// Нормализация для поиска без учёта огласовок.
+/// Должна совпадать с SQL-функцией `arabic_normalize` из миграций.
+pub fn normalize(text: &str) -> String {
+    text.chars()
+        .filter(|c| !is_diacritic(*c))
+        .map(fold_letter)
+        .collect()
+}
@@ -0,0 +1,39 @@
+use crate::arabic::is_arabic_char;
+use crate::arabic::normalize::is_diacritic;
+
+/// Разделители, которые допускаются при записи корня: "ك-ت-ب", "ك ت ب", "كتب".
+fn is_root_separator(c: char) -> bool {
+    c == '-' || c == '_' || c == '.' || c.is_whitespace()
+}
+
+/// Буква корня: все формы хамзы приводятся к ء, алиф-мадда и варианты алифа — к ا.
+fn fold_root_letter(c: char) -> char {
+    match c {
+        'أ' | 'إ' | 'ؤ' | 'ئ' => 'ء',
+        'آ' | 'ٱ' => 'ا',
+        'ى' => 'ي',
+        _ => c,
+    }
+}
+
+/// Приводит корень к каноническому виду "ك-ت-ب": без огласовок, буквы через дефис.
+/// Возвращает `None`, если это не трёх- или четырёхбуквенный арабский корень.
+pub fn canonical_root(input: &str) -> Option<String> {
+    let mut letters = Vec::new();
+
+    for c in input.chars() {
+        if is_diacritic(c) || is_root_separator(c) {
+            continue;
+        }
+        if !is_arabic_char(c) || !c.is_alphabetic() {
+            return None;
+        }
+        letters.push(fold_root_letter(c).to_string());
+    }
+
+    if !(3..=4).contains(&letters.len()) {
+        return None;
+    }
+
+    Some(letters.join("-"))
+}
@@ -0,0 +1,243 @@
+use crate::arabic::is_arabic_text;
+use crate::arabic::normalize::{fold_letter, segments, Segment};
+use crate::arabic::root::canonical_root;
+use crate::arabic::tokenize::PROCLITICS;
+
+/// Распространённые вазны (ف, ع и ل — места букв корня). Модели с одинаковыми буквами
+/// различаются огласовкой; при равной оценке выигрывает та, что выше в списке.
+const AWZAN: &[&str] = &[
+    "اِسْتِفْعَال",
+    "مُسْتَفْعِل",
+    "اِسْتَفْعَلَ",
+    "اِنْفِعَال",
+    "اِفْتِعَال",
+    "مَفَاعِيل",
+    "مُفَاعَلَة",
+    "مَفْعُول",
+    "مُنْفَعِل",
+    "مُفْتَعِل",
+    "اِنْفَعَلَ",
+    "اِفْتَعَلَ",
+    "مَفْعَلَة",
+    "مِفْعَال",
+    "تَفْعِيل",
+    "تَفَاعُل",
+    "مَفَاعِل",
+    "فَوَاعِل",
+    "أَفْعَال",
+    "إِفْعَال",
+    "فِعَالَة",
+    "فَعْلَان",
+    "فَاعِل",
+    "فَعِيل",
+    "فِعَال",
+    "فُعُول",
+    "مَفْعَل",
+    "مُفَعِّل",
+    "مُفْعِل",
+    "أَفْعَل",
+    "تَفَعَّلَ",
+    "يَفْعَلُ",
+    "فَعَلَ",
+    "فَعْل",
+    "فِعْل",
+    "فُعْل",
+    "فُعُل",
+];
+
+/// Окончания множественного числа, та марбута и слитные местоимения.
+const SUFFIXES: &[&str] = &["ات", "ون", "ين", "ان", "ها", "هم", "هن", "كم", "نا", "ة", "ه", "ي", "ك"];
+
+/// Предполагаемый корень и вазн слова.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Analysis {
+    /// Корень в каноническом виде, например "ك-ت-ب"
+    pub root: String,
+    /// Огласованная модель, например "فَاعِل"
+    pub pattern: String,
+}
+
+const FATHA: char = '\u{064E}';
+const DAMMA: char = '\u{064F}';
+const KASRA: char = '\u{0650}';
+const SHADDA: char = '\u{0651}';
+const SUKUN: char = '\u{0652}';
+
+fn is_radical_slot(c: char) -> bool {
+    matches!(c, 'ف' | 'ع' | 'ل')
+}
+
+/// Огласовка буквы (фатха, дамма, касра или сукун), если она указана.
+fn vowel(marks: &[char]) -> Option<char> {
+    marks.iter().copied().find(|m| matches!(*m, FATHA | DAMMA | KASRA | SUKUN))
+}
+
+/// Огласовки слова противоречат вазну: у буквы указан другой харакат или шадда не на своём месте.
+/// Буквы без огласовок и буквы, огласовка которых в вазне не задана (например, падежное окончание), подходят всегда.
+fn contradicts(template_marks: &[char], marks: &[char]) -> bool {
+    if let (Some(expected), Some(given)) = (vowel(template_marks), vowel(marks)) {
+        if expected != given {
+            return true;
+        }
+    }
+    !template_marks.is_empty() && !marks.is_empty() && template_marks.contains(&SHADDA) != marks.contains(&SHADDA)
+}
+
+/// Оценка совпадения основы с вазном.
+struct WaznMatch {
+    root: String,
+    /// Буквы шаблона, совпавшие дословно (без приведения хамзы)
+    exact: usize,
+    /// Буквы, огласовка которых в слове совпала с огласовкой вазна
+    vowels: usize,
+}
+
+fn match_wazn(stem: &[Segment], wazn: &str) -> Option<WaznMatch> {
+    let template = segments(wazn);
+    if template.len() != stem.len() {
+        return None;
+    }
+
+    let mut result = WaznMatch { root: String::new(), exact: 0, vowels: 0 };
+
+    for ((t, t_marks), (c, c_marks)) in template.iter().zip(stem) {
+        if contradicts(t_marks, c_marks) {
+            return None;
+        }
+
+        if *t == 'ع' && *c == 'ا' {
+            // Полый глагол: средняя буква корня (чаще всего و) стянулась в алиф, как в قَالَ от ق-و-ل
+            result.root.push('و');
+        } else if is_radical_slot(*t) {
+            // Алиф и та марбута не бывают буквами корня
+            if matches!(c, 'ا' | 'ة' | 'ى') {
+                return None;
+            }
+            result.root.push(*c);
+        } else if t == c {
+            result.exact += 1;
+        } else if fold_letter(*t) != fold_letter(*c) {
+            return None;
+        }
+
+        if !c_marks.is_empty() && c_marks == t_marks {
+            result.vowels += 1;
+        }
+    }
+
+    Some(result)
+}
+
+/// Все варианты основы после отсечения приставки и/или окончания
+/// вместе с числом отсечённых букв.
+fn stems(letters: &[Segment]) -> Vec<(&[Segment], usize)> {
+    let starts_with = |word: &[Segment], affix: &str| {
+        let affix: Vec<char> = affix.chars().collect();
+        word.len() >= affix.len() && word.iter().zip(&affix).all(|((c, _), a)| c == a)
+    };
+    let ends_with = |word: &[Segment], affix: &str| {
+        let affix: Vec<char> = affix.chars().collect();
+        word.len() >= affix.len() && word[word.len() - affix.len()..].iter().zip(&affix).all(|((c, _), a)| c == a)
+    };
+
+    let mut heads = vec![letters];
+    for prefix in PROCLITICS {
+        if starts_with(letters, prefix) {
+            heads.push(&letters[prefix.chars().count()..]);
+        }
+    }
+
+    let mut result = Vec::new();
+    for head in heads {
+        let stripped = letters.len() - head.len();
+        result.push((head, stripped));
+
+        for suffix in SUFFIXES {
+            if ends_with(head, suffix) {
+                let len = suffix.chars().count();
+                result.push((&head[..head.len() - len], stripped + len));
+            }
+        }
+    }
+
+    result.retain(|(stem, _)| stem.len() >= 3);
+    result
+}
+
+/// Лёгкий стеммер: отсекает артикль, предлоги и окончания и ищет вазн, под который подходит основа.
+/// Чем больше букв слова объясняет вазн и чем меньше отсечено, тем выше оценка варианта.
+pub fn analyze(term: &str) -> Option<Analysis> {
+    let term = term.trim();
+    if !is_arabic_text(term) || term.contains(char::is_whitespace) {
+        return None;
+    }
+
+    let letters = segments(term);
+
+    let mut best: Option<((i64, usize, usize), Analysis)> = None;
+
+    for (stem, stripped) in stems(&letters) {
+        for wazn in AWZAN {
+            let Some(found) = match_wazn(stem, wazn) else {
+                continue;
+            };
+            let Some(root) = canonical_root(&found.root) else {
+                continue;
+            };
+
+            let literals = (stem.len() - 3) as i64;
+            let score = (literals * 2 - stripped as i64, found.exact, found.vowels);
+
+            if best.as_ref().is_none_or(|(best_score, _)| score > *best_score) {
+                best = Some((score, Analysis { root, pattern: wazn.to_string() }));
+            }
+        }
+    }
+
+    best.map(|(_, analysis)| analysis)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_root_and_pattern() {
+        let cases = [
+            ("كَتَبَ", "ك-ت-ب", "فَعَلَ"),
+            ("كتب", "ك-ت-ب", "فَعَلَ"),
+            ("كُتُب", "ك-ت-ب", "فُعُل"),
+            ("دَرْس", "د-ر-س", "فَعْل"),
+            ("دَرْسٌ", "د-ر-س", "فَعْل"),
+            ("عِلْم", "ع-ل-م", "فِعْل"),
+            ("حُكْم", "ح-ك-م", "فُعْل"),
+            ("قَالَ", "ق-و-ل", "فَعَلَ"),
+            ("كَاتِب", "ك-ت-ب", "فَاعِل"),
+            ("كاتب", "ك-ت-ب", "فَاعِل"),
+            ("مَكْتُوب", "ك-ت-ب", "مَفْعُول"),
+            ("مُدَرِّس", "د-ر-س", "مُفَعِّل"),
+            ("مَدْرَسَة", "د-ر-س", "مَفْعَلَة"),
+            ("اِسْتِخْدَام", "خ-د-م", "اِسْتِفْعَال"),
+            ("وَالْكِتَابُ", "ك-ت-ب", "فِعَال"),
+        ];
+
+        for (term, root, pattern) in cases {
+            let analysis = analyze(term).unwrap_or_else(|| panic!("no analysis for {term}"));
+            assert_eq!((analysis.root.as_str(), analysis.pattern.as_str()), (root, pattern), "{term}");
+        }
+    }
+
+    #[test]
+    fn rejects_pattern_with_contradicting_harakat() {
+        assert!(match_wazn(&segments("كُتُب"), "فَعَلَ").is_none());
+        assert!(match_wazn(&segments("دَرْس"), "فَعَلَ").is_none());
+        assert!(match_wazn(&segments("كتب"), "فَعَلَ").is_some());
+    }
+
+    #[test]
+    fn ignores_non_arabic_and_phrases() {
+        assert_eq!(analyze("hello"), None);
+        assert_eq!(analyze("كتاب جديد"), None);
+        assert_eq!(analyze(""), None);
+    }
+}
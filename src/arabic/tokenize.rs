@@ -0,0 +1,237 @@
+use std::collections::HashMap;
+
+use crate::arabic::is_arabic_char;
+use crate::arabic::normalize::{is_diacritic, normalize, segments};
+
+/// Артикль, союзы و/ف и предлоги ب/ك/ل, которые пишутся слитно со словом.
+/// Длинные варианты идут первыми.
+pub const PROCLITICS: &[&str] = &["وال", "فال", "بال", "كال", "لل", "ال", "و", "ف", "ب", "ك", "ل"];
+
+/// Клитики с артиклем: после них идёт именно то слово, что стоит в словаре без артикля.
+const ARTICLES: &[&str] = &["وال", "فال", "بال", "كال", "لل", "ال"];
+
+/// Слитные местоимения (в нормализованном виде: ة уже стала ه).
+const ENCLITICS: &[&str] = &["هما", "كما", "هم", "هن", "ها", "كم", "كن", "نا", "ني", "ه", "ك", "ي"];
+
+/// После отсечения клитик от основы должно остаться хотя бы столько букв.
+const MIN_STEM_LEN: usize = 2;
+
+const SHADDA: char = '\u{0651}';
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    /// Арабское слово (буквы и огласовки)
+    Word,
+    /// Пробелы, знаки препинания, цифры и всё неарабское
+    Other,
+}
+
+#[derive(Debug, Clone)]
+pub struct Token {
+    pub text: String,
+    pub kind: TokenKind,
+}
+
+fn is_word_char(c: char) -> bool {
+    is_diacritic(c) || (is_arabic_char(c) && c.is_alphabetic())
+}
+
+/// Делит текст на арабские слова и промежутки между ними.
+/// Склеив `text` всех токенов, получим исходный текст без изменений.
+pub fn tokenize(text: &str) -> Vec<Token> {
+    let mut tokens: Vec<Token> = Vec::new();
+
+    for c in text.chars() {
+        let kind = if is_word_char(c) { TokenKind::Word } else { TokenKind::Other };
+
+        match tokens.last_mut() {
+            Some(last) if last.kind == kind => last.text.push(c),
+            _ => tokens.push(Token { text: c.to_string(), kind }),
+        }
+    }
+
+    tokens
+}
+
+/// Нормализованное слово без артикля (вместе со слитным союзом или предлогом перед ним):
+/// "والكتاب" -> "كتاب". Слово без артикля возвращается как есть.
+pub fn strip_article(normalized: &str) -> &str {
+    ARTICLES
+        .iter()
+        .filter_map(|article| normalized.strip_prefix(article))
+        .find(|stem| stem.chars().count() >= MIN_STEM_LEN)
+        .unwrap_or(normalized)
+}
+
+/// Нормализованные формы слова для поиска в словаре: сначала само слово,
+/// затем варианты без приставок-клитик и слитных местоимений, от меньшего отсечения к большему.
+/// "وَالْكِتَابُ" -> ["والكتاب", "الكتاب", "كتاب", ...]
+pub fn match_keys(word: &str) -> Vec<String> {
+    let full: Vec<char> = normalize(word).chars().collect();
+    // Однобуквенное местоимение не бывает с шаддой: в كُرْسِيّ буква ي — часть основы
+    let doubled_end = segments(word).last().is_some_and(|(_, marks)| marks.contains(&SHADDA));
+    let mut keys: Vec<(usize, String)> = Vec::new();
+
+    let mut heads: Vec<(usize, &[char])> = vec![(0, &full)];
+    for proclitic in PROCLITICS {
+        let proclitic: Vec<char> = proclitic.chars().collect();
+        if let Some(rest) = full.strip_prefix(proclitic.as_slice()) {
+            if rest.len() >= MIN_STEM_LEN {
+                heads.push((proclitic.len(), rest));
+            }
+        }
+    }
+
+    for (stripped, head) in heads {
+        keys.push((stripped, head.iter().collect()));
+
+        for enclitic in ENCLITICS {
+            let enclitic: Vec<char> = enclitic.chars().collect();
+            if doubled_end && enclitic.len() == 1 {
+                continue;
+            }
+            let Some(stem) = head.strip_suffix(enclitic.as_slice()) else {
+                continue;
+            };
+            if stem.len() < MIN_STEM_LEN {
+                continue;
+            }
+
+            let stem: String = stem.iter().collect();
+            // Перед местоимением та марбута пишется как ت: مدرستي -> مدرسة
+            if let Some(base) = stem.strip_suffix('ت') {
+                keys.push((stripped + enclitic.len(), format!("{}ه", base)));
+            }
+            keys.push((stripped + enclitic.len(), stem));
+        }
+    }
+
+    keys.sort_by_key(|(stripped, _)| *stripped);
+
+    let mut result: Vec<String> = Vec::new();
+    for (_, key) in keys {
+        if !result.contains(&key) {
+            result.push(key);
+        }
+    }
+    result
+}
+
+/// Словарь для сопоставления слов текста со словами урока
+/// без учёта огласовок, артикля и слитных предлогов/местоимений.
+#[derive(Default)]
+pub struct Lexicon {
+    entries: HashMap<String, Vec<i32>>,
+}
+
+impl Lexicon {
+    pub fn new() -> Self {
+        Lexicon::default()
+    }
+
+    /// Добавляет слово словаря. Термин с артиклем ("الكتاب") доступен и без него.
+    pub fn insert(&mut self, term: &str, id: i32) {
+        let key = normalize(term.trim());
+        let stem = strip_article(&key).to_string();
+
+        for key in [key, stem] {
+            let ids = self.entries.entry(key).or_default();
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+    }
+
+    /// Id слов словаря, которым соответствует слово текста, по первой совпавшей форме.
+    pub fn lookup(&self, word: &str) -> Option<&[i32]> {
+        match_keys(word)
+            .iter()
+            .find_map(|key| self.entries.get(key))
+            .map(Vec::as_slice)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_reassemble_into_the_original_text() {
+        let text = "ذَهَبَ الوَلَدُ إلى المدرسة، ثم عاد (2 pm).";
+        let tokens = tokenize(text);
+
+        assert_eq!(tokens.iter().map(|t| t.text.as_str()).collect::<String>(), text);
+        let words: Vec<&str> = tokens.iter().filter(|t| t.kind == TokenKind::Word).map(|t| t.text.as_str()).collect();
+        assert_eq!(words, ["ذَهَبَ", "الوَلَدُ", "إلى", "المدرسة", "ثم", "عاد"]);
+    }
+
+    #[test]
+    fn strips_article_with_attached_particle() {
+        assert_eq!(strip_article("والكتاب"), "كتاب");
+        assert_eq!(strip_article("للبيت"), "بيت");
+        assert_eq!(strip_article("كتاب"), "كتاب");
+        // Без артикля остаётся слишком короткая основа
+        assert_eq!(strip_article("الا"), "الا");
+    }
+
+    #[test]
+    fn match_keys_start_with_full_form() {
+        let keys = match_keys("وَالْكِتَابُ");
+        assert_eq!(keys[0], "والكتاب");
+        assert!(keys.contains(&"كتاب".to_string()));
+
+        let keys = match_keys("مدرستي");
+        assert_eq!(keys[0], "مدرستي");
+        assert!(keys.contains(&"مدرسه".to_string()));
+    }
+
+    #[test]
+    fn lexicon_strips_clitics() {
+        let mut lexicon = Lexicon::new();
+        lexicon.insert("كِتَاب", 1);
+        lexicon.insert("مَدْرَسَة", 2);
+        lexicon.insert("البيت", 3);
+
+        assert_eq!(lexicon.lookup("وَالْكِتَابُ"), Some(&[1][..]));
+        assert_eq!(lexicon.lookup("بكتابهم"), Some(&[1][..]));
+        assert_eq!(lexicon.lookup("مدرستي"), Some(&[2][..]));
+        assert_eq!(lexicon.lookup("بيتنا"), Some(&[3][..]));
+        assert_eq!(lexicon.lookup("قلم"), None);
+    }
+
+    #[test]
+    fn lexicon_prefers_the_least_stripped_form() {
+        let mut lexicon = Lexicon::new();
+        lexicon.insert("كُرْسِيّ", 1);
+        lexicon.insert("كَرَّسَ", 2);
+        lexicon.insert("كتاب", 3);
+        lexicon.insert("تاب", 4);
+
+        // ي здесь часть слова, а не местоимение «мой»
+        assert_eq!(lexicon.lookup("كرسي"), Some(&[1][..]));
+        assert_eq!(lexicon.lookup("الكرسي"), Some(&[1][..]));
+        assert_eq!(lexicon.lookup("وكرسي"), Some(&[1][..]));
+        // ك здесь первая буква слова, а не предлог «как»
+        assert_eq!(lexicon.lookup("كتاب"), Some(&[3][..]));
+        assert_eq!(lexicon.lookup("تاب"), Some(&[4][..]));
+    }
+
+    #[test]
+    fn doubled_final_letter_is_not_a_pronoun() {
+        let mut lexicon = Lexicon::new();
+        lexicon.insert("كَرَّسَ", 1);
+
+        assert_eq!(lexicon.lookup("كُرْسِيٌّ"), None);
+        assert!(!match_keys("كُرْسِيّ").contains(&"كرس".to_string()));
+        assert!(match_keys("كِتَابِي").contains(&"كتاب".to_string()));
+    }
+
+    #[test]
+    fn lexicon_keeps_homographs_together() {
+        let mut lexicon = Lexicon::new();
+        lexicon.insert("كَتَبَ", 1);
+        lexicon.insert("كُتُب", 2);
+
+        assert_eq!(lexicon.lookup("كتب"), Some(&[1, 2][..]));
+    }
+}
@@ -0,0 +1,374 @@
+use serde::Deserialize;
+
+use crate::arabic::is_arabic_char;
+use crate::arabic::normalize::is_diacritic;
+
+/// Схема латинской транслитерации.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum Scheme {
+    /// Обратимая посимвольная схема Тима Бакуолтера (ASCII)
+    #[serde(rename = "buckwalter")]
+    Buckwalter,
+    /// Немецкий стандарт DIN 31635
+    #[serde(rename = "din")]
+    Din,
+    /// Схема Библиотеки Конгресса ALA-LC
+    #[serde(rename = "ala-lc")]
+    AlaLc,
+}
+
+/// Таблица Бакуолтера: каждому арабскому символу соответствует ровно один ASCII-символ.
+const BUCKWALTER: &[(char, char)] = &[
+    ('ء', '\''), ('آ', '|'), ('أ', '>'), ('ؤ', '&'), ('إ', '<'), ('ئ', '}'),
+    ('ا', 'A'), ('ب', 'b'), ('ة', 'p'), ('ت', 't'), ('ث', 'v'), ('ج', 'j'),
+    ('ح', 'H'), ('خ', 'x'), ('د', 'd'), ('ذ', '*'), ('ر', 'r'), ('ز', 'z'),
+    ('س', 's'), ('ش', '$'), ('ص', 'S'), ('ض', 'D'), ('ط', 'T'), ('ظ', 'Z'),
+    ('ع', 'E'), ('غ', 'g'), ('ـ', '_'), ('ف', 'f'), ('ق', 'q'), ('ك', 'k'),
+    ('ل', 'l'), ('م', 'm'), ('ن', 'n'), ('ه', 'h'), ('و', 'w'), ('ى', 'Y'),
+    ('ي', 'y'), ('ً', 'F'), ('ٌ', 'N'), ('ٍ', 'K'), ('َ', 'a'), ('ُ', 'u'),
+    ('ِ', 'i'), ('ّ', '~'), ('ْ', 'o'), ('ٰ', '`'), ('ٱ', '{'),
+    ('،', ','), ('؛', ';'), ('؟', '?'),
+];
+
+const FATHA: char = '\u{064E}';
+const DAMMA: char = '\u{064F}';
+const KASRA: char = '\u{0650}';
+const FATHATAN: char = '\u{064B}';
+const DAMMATAN: char = '\u{064C}';
+const KASRATAN: char = '\u{064D}';
+const SHADDA: char = '\u{0651}';
+const SUPERSCRIPT_ALIF: char = '\u{0670}';
+
+/// "Солнечные" буквы, к которым уподобляется лям артикля.
+fn is_sun_letter(c: char) -> bool {
+    matches!(c, 'ت' | 'ث' | 'د' | 'ذ' | 'ر' | 'ز' | 'س' | 'ش' | 'ص' | 'ض' | 'ط' | 'ظ' | 'ل' | 'ن')
+}
+
+/// Согласный в научной транслитерации. DIN и ALA-LC различаются только
+/// записью межзубных, ج, خ, ش и غ (одна буква с диакритикой против диграфа).
+fn consonant(c: char, scheme: Scheme) -> Option<&'static str> {
+    let din = scheme == Scheme::Din;
+    let latin = match c {
+        'ء' | 'أ' | 'إ' | 'ؤ' | 'ئ' => "ʾ",
+        'ب' => "b",
+        'ت' => "t",
+        'ث' => if din { "ṯ" } else { "th" },
+        'ج' => if din { "ǧ" } else { "j" },
+        'ح' => "ḥ",
+        'خ' => if din { "ḫ" } else { "kh" },
+        'د' => "d",
+        'ذ' => if din { "ḏ" } else { "dh" },
+        'ر' => "r",
+        'ز' => "z",
+        'س' => "s",
+        'ش' => if din { "š" } else { "sh" },
+        'ص' => "ṣ",
+        'ض' => "ḍ",
+        'ط' => "ṭ",
+        'ظ' => "ẓ",
+        'ع' => "ʿ",
+        'غ' => if din { "ġ" } else { "gh" },
+        'ف' => "f",
+        'ق' => "q",
+        'ك' => "k",
+        'ل' => "l",
+        'م' => "m",
+        'ن' => "n",
+        'ه' => "h",
+        'و' => "w",
+        'ي' => "y",
+        _ => return None,
+    };
+    Some(latin)
+}
+
+/// Буква вместе с огласовками, стоящими после неё.
+struct Segment {
+    letter: char,
+    marks: Vec<char>,
+}
+
+impl Segment {
+    fn has(&self, mark: char) -> bool {
+        self.marks.contains(&mark)
+    }
+
+    /// Несёт ли буква собственную гласную или танвин (не сукун и не шадду).
+    fn has_vowel(&self) -> bool {
+        self.marks.iter().any(|m| matches!(*m, FATHA | DAMMA | KASRA | FATHATAN | DAMMATAN | KASRATAN))
+    }
+}
+
+/// و после даммы и ي после касры без собственной огласовки — долгие ū и ī.
+fn lengthens_vowel(seg: &Segment, out: &str) -> bool {
+    let short = if seg.letter == 'و' { 'u' } else { 'i' };
+    !seg.has_vowel() && !seg.has(SHADDA) && out.ends_with(short)
+}
+
+fn segments(word: &str) -> Vec<Segment> {
+    let mut result: Vec<Segment> = Vec::new();
+
+    for c in word.chars() {
+        if c == 'ـ' {
+            continue;
+        }
+        if is_diacritic(c) {
+            if let Some(last) = result.last_mut() {
+                last.marks.push(c);
+            }
+        } else {
+            result.push(Segment { letter: c, marks: Vec::new() });
+        }
+    }
+
+    result
+}
+
+/// Транслитерация одного слова по DIN 31635 или ALA-LC.
+/// Краткие гласные берутся из огласовок, поэтому неогласованный текст даёт только согласный остов.
+fn romanize_word(word: &str, scheme: Scheme) -> String {
+    let segs = segments(word);
+    let mut out = String::new();
+    let mut i = 0;
+    let mut article_end = None;
+
+    // Артикль: DIN уподобляет лям солнечной букве (aš-šams), ALA-LC пишет al- всегда
+    if segs.len() > 2 && matches!(segs[0].letter, 'ا' | 'ٱ') && segs[1].letter == 'ل' {
+        let next = segs[2].letter;
+        if scheme == Scheme::Din && is_sun_letter(next) && next != 'ل' {
+            out.push('a');
+            out.push_str(consonant(next, scheme).unwrap_or_default());
+            out.push('-');
+        } else {
+            out.push_str("al-");
+        }
+        i = 2;
+        article_end = Some(2);
+    }
+
+    while i < segs.len() {
+        let seg = &segs[i];
+        let at_start = out.is_empty();
+
+        match seg.letter {
+            // Начальная хамза в обеих системах не пишется
+            'أ' | 'إ' | 'ء' if at_start => {}
+            'آ' => out.push_str(if at_start { "ā" } else { "ʾā" }),
+            'ا' | 'ٱ' => {
+                // В начале слова это хамзат аль-васль: пишется только гласная.
+                // После танвина фатха алиф немой, в остальных случаях он удлиняет фатху.
+                if at_start || segs[i - 1].has(FATHATAN) {
+                } else if out.ends_with('a') {
+                    out.pop();
+                    out.push('ā');
+                } else {
+                    out.push('ā');
+                }
+            }
+            'ى' => {
+                if out.ends_with('a') {
+                    out.pop();
+                }
+                out.push('ā');
+            }
+            'ة' => {
+                if seg.has_vowel() {
+                    out.push('t');
+                } else {
+                    if !out.ends_with('a') {
+                        out.push('a');
+                    }
+                    if scheme == Scheme::AlaLc {
+                        out.push('h');
+                    }
+                }
+            }
+            'و' | 'ي' if lengthens_vowel(seg, &out) => {
+                let long = if out.pop() == Some('u') { 'ū' } else { 'ī' };
+                out.push(long);
+            }
+            c => match consonant(c, scheme) {
+                Some(latin) => {
+                    out.push_str(latin);
+                    // Шадда после артикля уже передана уподоблением
+                    if seg.has(SHADDA) && article_end != Some(i) {
+                        out.push_str(latin);
+                    }
+                }
+                None => out.push(c),
+            },
+        }
+
+        for mark in &seg.marks {
+            match *mark {
+                FATHA => out.push('a'),
+                DAMMA => out.push('u'),
+                KASRA => out.push('i'),
+                FATHATAN => out.push_str("an"),
+                DAMMATAN => out.push_str("un"),
+                KASRATAN => out.push_str("in"),
+                SUPERSCRIPT_ALIF => out.push('ā'),
+                _ => {}
+            }
+        }
+
+        i += 1;
+    }
+
+    out
+}
+
+fn buckwalter(text: &str) -> String {
+    text.chars()
+        .map(|c| {
+            BUCKWALTER
+                .iter()
+                .find(|(arabic, _)| *arabic == c)
+                .map_or(c, |(_, latin)| *latin)
+        })
+        .collect()
+}
+
+/// Переводит арабский текст в латиницу. Неарабские символы остаются как есть.
+pub fn transliterate(text: &str, scheme: Scheme) -> String {
+    if scheme == Scheme::Buckwalter {
+        return buckwalter(text);
+    }
+
+    let mut out = String::new();
+    let mut word = String::new();
+
+    for c in text.chars() {
+        if is_arabic_char(c) && c.is_alphabetic() || is_diacritic(c) {
+            word.push(c);
+            continue;
+        }
+        if !word.is_empty() {
+            out.push_str(&romanize_word(&word, scheme));
+            word.clear();
+        }
+        out.push(c);
+    }
+    if !word.is_empty() {
+        out.push_str(&romanize_word(&word, scheme));
+    }
+
+    out
+}
+
+/// Обратное преобразование Бакуолтера: редактор набирает "kataba" и получает "كَتَبَ".
+pub fn from_buckwalter(text: &str) -> String {
+    text.chars()
+        .map(|c| {
+            BUCKWALTER
+                .iter()
+                .find(|(_, latin)| *latin == c)
+                .map_or(c, |(arabic, _)| *arabic)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(scheme: Scheme, cases: &[(&str, &str)]) {
+        for (arabic, latin) in cases {
+            assert_eq!(transliterate(arabic, scheme), *latin, "{arabic}");
+        }
+    }
+
+    #[test]
+    fn buckwalter_maps_letters_and_marks() {
+        check(
+            Scheme::Buckwalter,
+            &[
+                ("كَتَبَ", "kataba"),
+                ("الشَّمْسُ", "Al$a~mosu"),
+                ("مَدْرَسَةٌ", "madorasapN"),
+                ("سُؤَالٌ", "su&aAlN"),
+                ("عَلَى", "EalaY"),
+                ("ما اسمك؟", "mA Asmk?"),
+            ],
+        );
+    }
+
+    #[test]
+    fn from_buckwalter_round_trip() {
+        for text in ["كَتَبَ", "الشَّمْسُ", "مَدْرَسَةٌ", "سُؤَالٌ", "الرَّحْمٰنِ", "شُكْرًا، يا أُسْتاذ؟"] {
+            assert_eq!(from_buckwalter(&transliterate(text, Scheme::Buckwalter)), text);
+        }
+        assert_eq!(from_buckwalter("kataba"), "كَتَبَ");
+    }
+
+    #[test]
+    fn din_assimilates_sun_letters() {
+        check(
+            Scheme::Din,
+            &[
+                ("الشَّمْسُ", "aš-šamsu"),
+                ("الرَّحْمٰنِ", "ar-raḥmāni"),
+                ("الْقَمَرُ", "al-qamaru"),
+                ("اللُّغَةُ", "al-luġatu"),
+            ],
+        );
+    }
+
+    #[test]
+    fn din_letters_and_ta_marbuta() {
+        check(
+            Scheme::Din,
+            &[
+                ("ثَلْجٌ", "ṯalǧun"),
+                ("خُبْزٌ", "ḫubzun"),
+                ("ذَهَبَ", "ḏahaba"),
+                ("شُكْرًا", "šukran"),
+                ("غُرْفَةٌ", "ġurfatun"),
+                ("مَدْرَسَة", "madrasa"),
+            ],
+        );
+    }
+
+    #[test]
+    fn ala_lc_keeps_article_and_digraphs() {
+        check(
+            Scheme::AlaLc,
+            &[
+                ("الشَّمْسُ", "al-shamsu"),
+                ("الرَّحْمٰنِ", "al-raḥmāni"),
+                ("الْقَمَرُ", "al-qamaru"),
+                ("ثَلْجٌ", "thaljun"),
+                ("خُبْزٌ", "khubzun"),
+                ("ذَهَبَ", "dhahaba"),
+                ("غُرْفَةٌ", "ghurfatun"),
+            ],
+        );
+    }
+
+    #[test]
+    fn ala_lc_ta_marbuta_long_vowels_and_shadda() {
+        check(
+            Scheme::AlaLc,
+            &[
+                // Та марбута в паузе — h, с огласовкой — t
+                ("مَدْرَسَة", "madrasah"),
+                ("مَدْرَسَةُ الْبَنَاتِ", "madrasatu al-banāti"),
+                // Долгие гласные
+                ("نُورٌ", "nūrun"),
+                ("كَبِيرٌ", "kabīrun"),
+                ("كِتَابٌ", "kitābun"),
+                ("عَلَى", "ʿalā"),
+                // Шадда удваивает согласный
+                ("مُدَرِّسٌ", "mudarrisun"),
+                ("سُؤَالٌ", "suʾālun"),
+                ("أَكَلَ", "akala"),
+            ],
+        );
+    }
+
+    #[test]
+    fn unvocalized_text_keeps_consonants_and_other_characters() {
+        assert_eq!(transliterate("كتاب", Scheme::AlaLc), "ktāb");
+        assert_eq!(transliterate("Lesson 1: كَتَبَ!", Scheme::Din), "Lesson 1: kataba!");
+    }
+}
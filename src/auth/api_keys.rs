@@ -0,0 +1,121 @@
+use axum::response::{IntoResponse, Json as AnswerJson};
+use axum::{extract::State, http::StatusCode};
+use sqlx::PgPool;
+
+use crate::auth::extractor::{AuthMethod, AuthUser};
+use crate::auth::seralizers::{ApiKey, ApiKeyScope, CreatedApiKey, RequestApiKey, Role};
+use crate::auth::tokens::{hash_token, random_token};
+use crate::lessons::state::AppState;
+use crate::utils::error::AppError;
+use crate::utils::extract::{Json, Path};
+
+const KEY_PREFIX: &str = "alk_";
+
+/// Ищет владельца действующего ключа и отмечает время последнего использования.
+pub async fn find_api_key_user(db_pool: &PgPool, key: &str) -> Result<Option<AuthUser>, sqlx::Error> {
+    let query = r#"
+        UPDATE api_keys k
+        SET last_used_at = NOW()
+        FROM users u
+        WHERE k.key_hash = $1
+          AND k.revoked_at IS NULL
+          AND u.id = k.user_id
+        RETURNING k.scope, u.id, u.role
+    "#;
+
+    let row = sqlx::query_as::<_, (ApiKeyScope, i32, Role)>(query)
+        .bind(hash_token(key))
+        .fetch_optional(db_pool)
+        .await?;
+
+    Ok(row.map(|(scope, user_id, role)| AuthUser {
+        user_id,
+        role,
+        method: AuthMethod::ApiKey { scope },
+    }))
+}
+
+/// Управлять ключами можно только из обычной сессии, а не другим ключом.
+fn session_only(user: &AuthUser) -> Result<(), AppError> {
+    match user.method {
+        AuthMethod::Token(_) => Ok(()),
+        AuthMethod::ApiKey { .. } => Err(AppError::forbidden("API keys cannot be managed with an API key")),
+    }
+}
+
+pub async fn get_api_keys(
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> Result<impl IntoResponse, AppError> {
+    session_only(&user)?;
+
+    let query = r#"
+        SELECT id, name, key_prefix, scope, created_at, last_used_at, revoked_at
+        FROM api_keys
+        WHERE user_id = $1
+        ORDER BY id
+    "#;
+
+    let keys = sqlx::query_as::<_, ApiKey>(query)
+        .bind(user.user_id)
+        .fetch_all(&state.db_pool)
+        .await?;
+
+    Ok((StatusCode::OK, AnswerJson(keys)))
+}
+
+pub async fn create_api_key(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(payload): Json<RequestApiKey>,
+) -> Result<impl IntoResponse, AppError> {
+    session_only(&user)?;
+
+    let name = payload.name.trim();
+    if name.is_empty() || name.chars().count() > 100 {
+        return Err(AppError::bad_request("the name field must be between 1 and 100 characters"));
+    }
+
+    let key = format!("{}{}", KEY_PREFIX, random_token(32));
+
+    let query = r#"
+        INSERT INTO api_keys (user_id, name, key_prefix, key_hash, scope)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING id, name, key_prefix, scope, created_at, last_used_at, revoked_at
+    "#;
+
+    let api_key = sqlx::query_as::<_, ApiKey>(query)
+        .bind(user.user_id)
+        .bind(name)
+        .bind(&key[..KEY_PREFIX.len() + 8])
+        .bind(hash_token(&key))
+        .bind(payload.scope)
+        .fetch_one(&state.db_pool)
+        .await?;
+
+    Ok((StatusCode::CREATED, AnswerJson(CreatedApiKey { key, api_key })))
+}
+
+pub async fn revoke_api_key(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<i32>,
+) -> Result<impl IntoResponse, AppError> {
+    session_only(&user)?;
+
+    let query = r#"
+        UPDATE api_keys
+        SET revoked_at = NOW()
+        WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL
+        RETURNING id
+    "#;
+
+    sqlx::query(query)
+        .bind(id)
+        .bind(user.user_id)
+        .fetch_optional(&state.db_pool)
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("API key with id {} not found", id)))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
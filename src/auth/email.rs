@@ -0,0 +1,237 @@
+use axum::response::IntoResponse;
+use axum::{extract::State, http::StatusCode};
+use bcrypt::hash;
+use chrono::{Duration, Utc};
+use sqlx::PgConnection;
+
+use crate::auth::extractor::AuthUser;
+use crate::auth::handlers::validate_password;
+use crate::auth::seralizers::{RequestEmailToken, RequestForgotPassword, RequestResetPassword};
+use crate::auth::tokens::{hash_token, random_token, revoke_user_sessions};
+use crate::lessons::state::AppState;
+use crate::mailer::Email;
+use crate::utils::error::AppError;
+use crate::utils::extract::Json;
+
+const VERIFY_EMAIL_TTL_HOURS: i64 = 48;
+const RESET_PASSWORD_TTL_HOURS: i64 = 1;
+
+#[derive(Debug, Clone, Copy)]
+pub enum EmailPurpose {
+    VerifyEmail,
+    ResetPassword,
+}
+
+impl EmailPurpose {
+    fn as_str(&self) -> &'static str {
+        match self {
+            EmailPurpose::VerifyEmail => "verify_email",
+            EmailPurpose::ResetPassword => "reset_password",
+        }
+    }
+}
+
+fn app_base_url() -> String {
+    std::env::var("APP_BASE_URL").unwrap_or_else(|_| "http://localhost:3000".to_string())
+}
+
+fn invalid_token() -> AppError {
+    AppError::bad_request("Invalid or expired token")
+}
+
+async fn create_email_token(
+    conn: &mut PgConnection,
+    user_id: i32,
+    purpose: EmailPurpose,
+    ttl: Duration,
+) -> Result<String, sqlx::Error> {
+    let token = random_token(32);
+
+    let query = r#"
+        INSERT INTO email_tokens (user_id, token_hash, purpose, expires_at)
+        VALUES ($1, $2, $3, $4)
+    "#;
+
+    sqlx::query(query)
+        .bind(user_id)
+        .bind(hash_token(&token))
+        .bind(purpose.as_str())
+        .bind(Utc::now() + ttl)
+        .execute(conn)
+        .await?;
+
+    Ok(token)
+}
+
+/// Помечает токен использованным и возвращает id пользователя, если токен действителен.
+async fn consume_email_token(
+    conn: &mut PgConnection,
+    token: &str,
+    purpose: EmailPurpose,
+) -> Result<Option<i32>, sqlx::Error> {
+    let query = r#"
+        UPDATE email_tokens
+        SET used_at = NOW()
+        WHERE token_hash = $1
+          AND purpose = $2
+          AND used_at IS NULL
+          AND expires_at > NOW()
+        RETURNING user_id
+    "#;
+
+    sqlx::query_scalar::<_, i32>(query)
+        .bind(hash_token(token))
+        .bind(purpose.as_str())
+        .fetch_optional(conn)
+        .await
+}
+
+/// Создаёт токен подтверждения и отправляет письмо со ссылкой.
+pub async fn send_verification_email(state: &AppState, user_id: i32, email: &str) -> Result<(), String> {
+    let mut conn = state.db_pool.acquire().await.map_err(|e| e.to_string())?;
+
+    let token = create_email_token(&mut conn, user_id, EmailPurpose::VerifyEmail, Duration::hours(VERIFY_EMAIL_TTL_HOURS))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let link = format!("{}/verify-email?token={}", app_base_url(), token);
+
+    state
+        .mailer
+        .send(Email {
+            to: email.to_string(),
+            subject: "Подтверждение email".to_string(),
+            body: format!("Чтобы подтвердить адрес, перейдите по ссылке:\n{}\n", link),
+        })
+        .await
+        .map_err(|e| e.to_string())
+}
+
+pub async fn verify_email(
+    State(state): State<AppState>,
+    Json(payload): Json<RequestEmailToken>,
+) -> Result<impl IntoResponse, AppError> {
+    let mut tx = state.db_pool.begin().await?;
+
+    let user_id = consume_email_token(&mut tx, &payload.token, EmailPurpose::VerifyEmail)
+        .await?
+        .ok_or_else(invalid_token)?;
+
+    sqlx::query("UPDATE users SET email_verified_at = NOW(), updated_at = NOW() WHERE id = $1")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn resend_verification(
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> Result<impl IntoResponse, AppError> {
+    user.require_write()?;
+
+    let query = r#"
+        SELECT email, email_verified_at IS NOT NULL
+        FROM users
+        WHERE id = $1
+    "#;
+
+    let result = sqlx::query_as::<_, (Option<String>, bool)>(query)
+        .bind(user.user_id)
+        .fetch_optional(&state.db_pool)
+        .await?;
+
+    match result {
+        Some((_, true)) => Err(AppError::conflict("Email is already verified")),
+        Some((Some(email), false)) => {
+            send_verification_email(&state, user.user_id, &email)
+                .await
+                .map_err(|e| AppError::internal("Failed to send verification email", e))?;
+            Ok(StatusCode::ACCEPTED)
+        }
+        _ => Err(AppError::not_found("User has no email address")),
+    }
+}
+
+/// Всегда отвечает 202, чтобы по ответу нельзя было узнать, зарегистрирован ли адрес.
+pub async fn forgot_password(
+    State(state): State<AppState>,
+    Json(payload): Json<RequestForgotPassword>,
+) -> Result<impl IntoResponse, AppError> {
+    let user = sqlx::query_scalar::<_, i32>("SELECT id FROM users WHERE email = $1")
+        .bind(&payload.email)
+        .fetch_optional(&state.db_pool)
+        .await?;
+
+    let Some(user_id) = user else {
+        return Ok(StatusCode::ACCEPTED);
+    };
+
+    // Токен создаётся и письмо отправляется в фоне: иначе по времени ответа
+    // было бы видно, что адрес зарегистрирован
+    tokio::spawn(async move {
+        if let Err(err) = send_reset_email(&state, user_id, payload.email).await {
+            eprintln!("Failed to send reset email: {}", err);
+        }
+    });
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+async fn send_reset_email(state: &AppState, user_id: i32, email: String) -> Result<(), String> {
+    let mut conn = state.db_pool.acquire().await.map_err(|e| e.to_string())?;
+
+    let token = create_email_token(&mut conn, user_id, EmailPurpose::ResetPassword, Duration::hours(RESET_PASSWORD_TTL_HOURS))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let link = format!("{}/reset-password?token={}", app_base_url(), token);
+
+    state
+        .mailer
+        .send(Email {
+            to: email,
+            subject: "Восстановление пароля".to_string(),
+            body: format!(
+                "Чтобы задать новый пароль, перейдите по ссылке:\n{}\n\nЕсли вы не запрашивали сброс пароля, просто проигнорируйте это письмо.\n",
+                link
+            ),
+        })
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Задаёт новый пароль по токену из письма и завершает все активные сессии пользователя.
+pub async fn reset_password(
+    State(state): State<AppState>,
+    Json(payload): Json<RequestResetPassword>,
+) -> Result<impl IntoResponse, AppError> {
+    let check_result_password = validate_password(&payload.password);
+    if !check_result_password.0 {
+        return Err(AppError::bad_request(check_result_password.1));
+    }
+
+    let hashed = hash(&payload.password, 4)
+        .map_err(|e| AppError::internal("Ошибка хеширования пароля", e))?;
+
+    let mut tx = state.db_pool.begin().await?;
+
+    let user_id = consume_email_token(&mut tx, &payload.token, EmailPurpose::ResetPassword)
+        .await?
+        .ok_or_else(invalid_token)?;
+
+    sqlx::query("UPDATE users SET password_hash = $1, updated_at = NOW() WHERE id = $2")
+        .bind(hashed)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    revoke_user_sessions(&mut tx, user_id).await?;
+
+    tx.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
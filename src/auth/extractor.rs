@@ -0,0 +1,97 @@
+use axum::extract::{FromRequestParts, OptionalFromRequestParts};
+use axum::http::request::Parts;
+use jsonwebtoken::{decode, DecodingKey, Validation};
+
+use crate::auth::api_keys::find_api_key_user;
+use crate::auth::seralizers::{ApiKeyScope, Claims, Role};
+use crate::auth::tokens::is_token_revoked;
+use crate::lessons::state::AppState;
+use crate::utils::error::AppError;
+
+pub fn jwt_secret() -> String {
+    std::env::var("JWT_SECRET").expect("JWT_SECRET must be set")
+}
+
+/// Чем подтверждена личность пользователя.
+pub enum AuthMethod {
+    Token(Claims),
+    ApiKey { scope: ApiKeyScope },
+}
+
+/// Экстрактор авторизованного пользователя.
+/// Принимает заголовок Authorization в двух видах:
+/// `Bearer <jwt>` — проверяются подпись, срок действия и что токен не был отозван;
+/// `ApiKey <ключ>` — персональный ключ пользователя, хранящийся в базе в виде хеша.
+pub struct AuthUser {
+    pub user_id: i32,
+    pub role: Role,
+    pub method: AuthMethod,
+}
+
+impl AuthUser {
+    /// Claims access-токена, если пользователь вошёл по JWT, а не по API-ключу.
+    pub fn claims(&self) -> Option<&Claims> {
+        match &self.method {
+            AuthMethod::Token(claims) => Some(claims),
+            AuthMethod::ApiKey { .. } => None,
+        }
+    }
+}
+
+impl FromRequestParts<AppState> for AuthUser {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        let auth_header = parts
+            .headers
+            .get("Authorization")
+            .and_then(|value| value.to_str().ok())
+            .ok_or_else(|| AppError::unauthorized("Missing authorization header"))?;
+
+        if let Some(key) = auth_header.strip_prefix("ApiKey ") {
+            return find_api_key_user(&state.db_pool, key)
+                .await?
+                .ok_or_else(|| AppError::unauthorized("Invalid API key"));
+        }
+
+        let token = auth_header
+            .strip_prefix("Bearer ")
+            .ok_or_else(|| AppError::unauthorized("Invalid authorization scheme"))?;
+
+        let secret_jwt = jwt_secret();
+
+        let claims = match decode::<Claims>(token, &DecodingKey::from_secret(secret_jwt.as_bytes()), &Validation::default()) {
+            Ok(data) => data.claims,
+            Err(e) => {
+                eprintln!("Invalid token: {}", e);
+                return Err(AppError::unauthorized("Invalid or expired token"));
+            }
+        };
+
+        if is_token_revoked(&state.db_pool, &claims).await? {
+            return Err(AppError::unauthorized("Token has been revoked"));
+        }
+
+        Ok(AuthUser {
+            user_id: claims.uid,
+            role: claims.role,
+            method: AuthMethod::Token(claims),
+        })
+    }
+}
+
+/// `Option<AuthUser>`: `None` для анонимного запроса без заголовка Authorization.
+/// Неверный или отозванный токен по-прежнему даёт 401, а не молча превращается в аноним.
+impl OptionalFromRequestParts<AppState> for AuthUser {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Option<Self>, Self::Rejection> {
+        if !parts.headers.contains_key("Authorization") {
+            return Ok(None);
+        }
+
+        <AuthUser as FromRequestParts<AppState>>::from_request_parts(parts, state)
+            .await
+            .map(Some)
+    }
+}
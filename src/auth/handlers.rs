@@ -1,17 +1,23 @@
-use axum::http::HeaderMap;
+use std::net::SocketAddr;
+
 use axum::response::{IntoResponse, Json as AnswerJson};
 use axum::{
-    extract::{Json, State},
+    extract::{ConnectInfo, State},
     http::StatusCode,
 };
 
-use jsonwebtoken::{encode, decode, Header, Validation, EncodingKey, DecodingKey};
-use serde_json::json;
 use bcrypt::{hash, verify};
 
 
 use crate::lessons::state::AppState;
-use crate::auth::seralizers::{RequestUsers, Users, LoginInfo, LoginReponse, Claims};
+use crate::auth::email::send_verification_email;
+use crate::auth::extractor::AuthUser;
+use crate::auth::permissions::Admin;
+use crate::auth::seralizers::{RequestUsers, RequestRole, RequestRefresh, Role, Users, LoginInfo, LoginReponse};
+use crate::auth::throttle::{locked_until, register_failure, reset_failures};
+use crate::auth::tokens::{hash_token, issue_access_token, revoke_user_sessions, store_refresh_token};
+use crate::utils::error::AppError;
+use crate::utils::extract::{Json, Path};
 
 
 
@@ -19,86 +25,198 @@ use crate::auth::seralizers::{RequestUsers, Users, LoginInfo, LoginReponse, Clai
 pub async fn register(
     State(state): State<AppState>,
     Json(payload): Json<RequestUsers>
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, AppError> {
 
 
     let check_result_password = validate_password(&payload.password);
-    if check_result_password.0 == false {
-        return (StatusCode::BAD_REQUEST, AnswerJson(json!({"error": check_result_password.1}))).into_response();
+    if !check_result_password.0 {
+        return Err(AppError::bad_request(check_result_password.1));
     }
 
     // Проверяем имя пользователя
     let check_result_username = validate_username(&payload.username);
-    if check_result_username.0 == false {
-        return (StatusCode::BAD_REQUEST, AnswerJson(json!({"error": check_result_username.1}))).into_response();
+    if !check_result_username.0 {
+        return Err(AppError::bad_request(check_result_username.1));
     }
     
     let query =   r#"
     INSERT INTO users (username, password_hash, email, created_at, updated_at)
     VALUES ($1, $2, $3, NOW(), NOW())
-    RETURNING id, username, password_hash, email, created_at, updated_at
+    RETURNING id, username, email, role, email_verified_at, created_at, updated_at
     "#;
 
-    let hashed = match hash(&payload.password, 4) {
-        Ok(h) => h,
-        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Ошибка хеширования пароля").into_response(),
-    };
+    let hashed = hash(&payload.password, 4)
+        .map_err(|e| AppError::internal("Ошибка хеширования пароля", e))?;
 
-    let result = sqlx::query_as::<_, Users>(query)
+    let user = sqlx::query_as::<_, Users>(query)
         .bind(&payload.username)
         .bind(hashed)
         .bind(&payload.email)
         .fetch_one(&state.db_pool)
-        .await;
-    
+        .await?;
 
-    match result {
-        Ok(u) => (StatusCode::OK, AnswerJson(u)).into_response(),
-        Err(_) => (StatusCode::INTERNAL_SERVER_ERROR).into_response()   
+    if let Err(err) = send_verification_email(&state, user.id, &user.email).await {
+        eprintln!("Failed to send verification email: {}", err);
     }
-    
+
+    Ok((StatusCode::OK, AnswerJson(user)))
 }
 
 
-async fn login(
+pub async fn login(
     State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
     Json(login_info): Json<LoginInfo>
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, AppError> {
     let username = &login_info.username;
     let password = &login_info.password;
+    let ip = addr.ip().to_string();
 
-    match is_valid_user(&state, &username, &password).await {
-        UserValidationResult::DatabaseError => StatusCode::INTERNAL_SERVER_ERROR.into_response(), 
-        UserValidationResult::InvalidCredentials => (StatusCode::UNAUTHORIZED, AnswerJson(json!({
-            "status": "error",
-            "message": "Invalid credentials"
-        }))).into_response(),
-        UserValidationResult::Valid => {
-
-            let claims = Claims {
-                sub: username.clone(),
-                exp: (chrono::Utc::now() + chrono::Duration::hours(1)).timestamp() as usize
-            };
-
-            let secret_jwt = std::env::var("JWT_SECRET").expect("JWT_SECRET must be set");
-
-            let token = match encode(&Header::default(), &claims, &EncodingKey::from_secret(secret_jwt.as_bytes())) {
-                Ok(token) => token,
-                Err(e) => {
-                    eprint!("Error Generation Token {}", e);
-                    return StatusCode::INTERNAL_SERVER_ERROR.into_response();
-                }
-            };
-            (StatusCode::OK, Json(LoginReponse { token })).into_response()
+    if let Some(until) = locked_until(&state.db_pool, username, &ip).await? {
+        return Err(AppError::TooManyRequests {
+            message: "Too many failed login attempts, try again later".to_string(),
+            retry_after: (until - chrono::Utc::now()).num_seconds().max(1),
+        });
+    }
+
+    match is_valid_user(&state, username, password).await {
+        UserValidationResult::DatabaseError => Err(AppError::Internal), 
+        UserValidationResult::InvalidCredentials => {
+            if let Err(err) = register_failure(&state.db_pool, username, &ip, "invalid_credentials").await {
+                eprintln!("Failed to register failed login: {:?}", err);
+            }
+            Err(AppError::unauthorized("Invalid credentials"))
+        }
+        UserValidationResult::Valid(user_id, role) => {
+            if let Err(err) = reset_failures(&state.db_pool, username).await {
+                eprintln!("Failed to reset login throttle: {:?}", err);
+            }
+
+            let token = issue_access_token(user_id, username, role)
+                .map_err(|e| AppError::internal("Error Generation Token", e))?;
+
+            let mut conn = state.db_pool.acquire().await?;
+            let (_, refresh_token) = store_refresh_token(&mut conn, user_id).await?;
+
+            Ok((StatusCode::OK, AnswerJson(LoginReponse { token, refresh_token })))
         } 
     } 
 }
 
 
+/// Обмен refresh-токена на новую пару токенов. Старый refresh-токен отзывается (ротация).
+/// Повторное предъявление уже отозванного токена считается кражей: отзываем все сессии пользователя.
+pub async fn refresh(
+    State(state): State<AppState>,
+    Json(payload): Json<RequestRefresh>,
+) -> Result<impl IntoResponse, AppError> {
+    let invalid_token = || AppError::unauthorized("Invalid refresh token");
+
+    let mut tx = state.db_pool.begin().await?;
+
+    let query = r#"
+        SELECT rt.id, rt.user_id, rt.revoked_at IS NOT NULL, rt.expires_at <= NOW(), u.username, u.role
+        FROM refresh_tokens rt
+        JOIN users u ON u.id = rt.user_id
+        WHERE rt.token_hash = $1
+        FOR UPDATE OF rt
+    "#;
+
+    let (token_id, user_id, is_revoked, is_expired, username, role) =
+        sqlx::query_as::<_, (i32, i32, bool, bool, String, Role)>(query)
+            .bind(hash_token(&payload.refresh_token))
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or_else(invalid_token)?;
+
+    if is_revoked {
+        revoke_user_sessions(&mut tx, user_id).await?;
+        tx.commit().await?;
+        return Err(invalid_token());
+    }
+
+    if is_expired {
+        return Err(invalid_token());
+    }
+
+    let (new_id, refresh_token) = store_refresh_token(&mut tx, user_id).await?;
+
+    sqlx::query("UPDATE refresh_tokens SET revoked_at = NOW(), replaced_by = $1 WHERE id = $2")
+        .bind(new_id)
+        .bind(token_id)
+        .execute(&mut *tx)
+        .await?;
+
+    let token = issue_access_token(user_id, &username, role)
+        .map_err(|e| AppError::internal("Error Generation Token", e))?;
+
+    tx.commit().await?;
+
+    Ok((StatusCode::OK, AnswerJson(LoginReponse { token, refresh_token })))
+}
+
+
+/// Выход: отзывает переданный refresh-токен и текущий access-токен.
+pub async fn logout(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(payload): Json<RequestRefresh>,
+) -> Result<impl IntoResponse, AppError> {
+    let claims = user
+        .claims()
+        .ok_or_else(|| AppError::bad_request("API keys cannot log out, revoke the key instead"))?;
+
+    let mut tx = state.db_pool.begin().await?;
+
+    sqlx::query(
+        "UPDATE refresh_tokens SET revoked_at = NOW() WHERE token_hash = $1 AND user_id = $2 AND revoked_at IS NULL",
+    )
+    .bind(hash_token(&payload.refresh_token))
+    .bind(user.user_id)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        "INSERT INTO revoked_tokens (jti, expires_at) VALUES ($1, to_timestamp($2)) ON CONFLICT (jti) DO NOTHING",
+    )
+    .bind(&claims.jti)
+    .bind(claims.exp as f64)
+    .execute(&mut *tx)
+    .await?;
+
+    // Заодно чистим записи, срок действия которых уже истёк
+    sqlx::query("DELETE FROM revoked_tokens WHERE expires_at <= NOW()")
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+
+/// Администратор завершает все сессии пользователя.
+pub async fn revoke_sessions(
+    State(state): State<AppState>,
+    _admin: Admin,
+    Path(user_id): Path<i32>,
+) -> Result<impl IntoResponse, AppError> {
+    let mut conn = state.db_pool.acquire().await?;
+
+    if !revoke_user_sessions(&mut conn, user_id).await? {
+        return Err(AppError::not_found(format!("User with id {} not found", user_id)));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+
+
+
 
 #[derive(Debug)]
 pub enum UserValidationResult {
-    Valid,               // Пользователь существует и пароль верный
+    Valid(i32, Role),    // Пользователь существует и пароль верный
     InvalidCredentials,  // Пользователь не существует или пароль неверный
     DatabaseError,       // Ошибка базы данных
 }
@@ -107,12 +225,12 @@ pub enum UserValidationResult {
 
 pub async fn is_valid_user(state: &AppState, username: &str, password: &str) -> UserValidationResult {
     let query = r#"
-        SELECT password_hash 
+        SELECT id, password_hash, role
         FROM users 
         WHERE username = $1
     "#;
 
-    let result = match sqlx::query_scalar::<_, String>(query)
+    let result = match sqlx::query_as::<_, (i32, String, Role)>(query)
         .bind(username)
         .fetch_optional(&state.db_pool)
         .await {
@@ -122,9 +240,9 @@ pub async fn is_valid_user(state: &AppState, username: &str, password: &str) ->
 
 
     match result {
-            Some(hash) => {
+            Some((id, hash, role)) => {
                 match verify(password, &hash) {
-                    Ok(true) => UserValidationResult::Valid,
+                    Ok(true) => UserValidationResult::Valid(id, role),
                     _ => UserValidationResult::InvalidCredentials,
                 }
             },
@@ -132,28 +250,61 @@ pub async fn is_valid_user(state: &AppState, username: &str, password: &str) ->
         }
 }
 
-async fn get_info_handler(header_map: HeaderMap) -> impl IntoResponse {
-    let secret_jwt = std::env::var("JWT_SECRET").expect("JWT_SECRET must be set");
-
-    if let Some(auth_header) = header_map.get("Authorization") {
-        if let Ok(auth_header_str) = auth_header.to_str() {
-            if auth_header_str.starts_with("Bearer ") {
-                let token = auth_header_str.trim_start_matches("Bearer ").to_string();
-
-                match decode::<Claims>(&token, &DecodingKey::from_secret(&secret_jwt.as_bytes()),  &Validation::default()) {
-                    Ok(_) => {
-                        let info = "You are valid here is info".to_string();
-                        return AnswerJson(info).into_response();
-                    }
-                    Err(e) => {
-                        eprint!("Error Generation Token {}", e);
-                        return StatusCode::UNAUTHORIZED.into_response()
-                    }
-                }
-            }
-        };
+pub async fn get_info_handler(
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> Result<impl IntoResponse, AppError> {
+    let query = r#"
+        SELECT id, username, email, role, email_verified_at, created_at, updated_at
+        FROM users
+        WHERE id = $1
+    "#;
+
+    let user = sqlx::query_as::<_, Users>(query)
+        .bind(user.user_id)
+        .fetch_optional(&state.db_pool)
+        .await?
+        .ok_or_else(|| AppError::unauthorized("User no longer exists"))?;
+
+    Ok((StatusCode::OK, AnswerJson(user)))
+}
+
+
+pub async fn update_user_role(
+    State(state): State<AppState>,
+    _admin: Admin,
+    Path(user_id): Path<i32>,
+    Json(payload): Json<RequestRole>,
+) -> Result<impl IntoResponse, AppError> {
+    let mut tx = state.db_pool.begin().await?;
+
+    let current = sqlx::query_scalar::<_, Role>("SELECT role FROM users WHERE id = $1 FOR UPDATE")
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("User with id {} not found", user_id)))?;
+
+    let query = r#"
+        UPDATE users
+        SET role = $1, updated_at = NOW()
+        WHERE id = $2
+        RETURNING id, username, email, role, email_verified_at, created_at, updated_at
+    "#;
+
+    let user = sqlx::query_as::<_, Users>(query)
+        .bind(payload.role)
+        .bind(user_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+    // Роль зашита в access-токены: старые токены со старой ролью больше не должны работать
+    if current != payload.role {
+        revoke_user_sessions(&mut tx, user_id).await?;
     }
-    StatusCode::UNAUTHORIZED.into_response()
+
+    tx.commit().await?;
+
+    Ok((StatusCode::OK, AnswerJson(user)))
 }
 
 
@@ -188,11 +339,32 @@ pub fn validate_password(password: &str) -> (bool, String) {
         return (false, "the password field must be more than 6 characters".to_string());
     }
     // Проверка, что пароль содержит хотя бы одну цифру и одну букву
-    let has_digit = password.chars().any(|c| c.is_digit(10));
+    let has_digit = password.chars().any(|c| c.is_ascii_digit());
     let has_letter = password.chars().any(|c| c.is_alphabetic());
 
-    if has_digit && has_letter == false {
+    if has_digit && !has_letter {
         return (false, "The password must contain at least one number and one letter".to_string());
     }
     (true, "Everything is correct".to_string())
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_password_rejects_digits_only() {
+        assert!(!validate_password("1234567").0);
+    }
+
+    #[test]
+    fn validate_password_accepts_letters_only_and_mixed() {
+        assert!(validate_password("abcdefg").0);
+        assert!(validate_password("abc123").0);
+    }
+
+    #[test]
+    fn validate_password_rejects_empty_and_short() {
+        assert!(!validate_password("").0);
+        assert!(!validate_password("ab1").0);
+    }
+}
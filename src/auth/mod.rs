@@ -1,2 +1,9 @@
+pub mod api_keys;
+pub mod email;
+pub mod extractor;
+pub mod permissions;
 pub mod seralizers;
-pub mod handlers;
\ No newline at end of file
+pub mod handlers;
+pub mod routes;
+pub mod tokens;
+pub mod throttle;
@@ -0,0 +1,140 @@
+use std::ops::Deref;
+
+use axum::extract::FromRequestParts;
+use axum::http::request::Parts;
+
+use crate::auth::extractor::{AuthMethod, AuthUser};
+use crate::auth::seralizers::{ApiKeyScope, Role};
+use crate::lessons::state::AppState;
+use crate::utils::error::AppError;
+
+/// Действия, доступ к которым зависит от роли.
+#[derive(Debug, Clone, Copy)]
+pub enum Permission {
+    /// Создание, изменение и удаление учебников, уроков и слов
+    EditContent,
+    /// Работа с учениками: просмотр их прогресса
+    ManageClasses,
+    /// Назначение ролей другим пользователям
+    ManageUsers,
+    /// Запись собственного прогресса: уроки, тесты и повторение слов. Доступно всем ролям,
+    /// у ученика других прав на изменение нет
+    RecordProgress,
+}
+
+impl Role {
+    pub fn has_permission(&self, permission: Permission) -> bool {
+        match permission {
+            Permission::EditContent => matches!(self, Role::Admin | Role::Editor),
+            Permission::ManageClasses => matches!(self, Role::Admin | Role::Teacher),
+            Permission::ManageUsers => matches!(self, Role::Admin),
+            Permission::RecordProgress => true,
+        }
+    }
+}
+
+impl AuthUser {
+    /// Ключ с правом только на чтение не даёт никаких прав на изменение,
+    /// даже если его владелец — редактор или администратор.
+    pub fn require_write(&self) -> Result<(), AppError> {
+        if let AuthMethod::ApiKey { scope: ApiKeyScope::Read } = self.method {
+            return Err(AppError::forbidden("API key is read-only"));
+        }
+        Ok(())
+    }
+
+    pub fn require(&self, permission: Permission) -> Result<(), AppError> {
+        self.require_write()?;
+
+        if self.role.has_permission(permission) {
+            Ok(())
+        } else {
+            Err(AppError::forbidden("Insufficient permissions"))
+        }
+    }
+}
+
+/// Пользователь, который может записывать свой прогресс: любая роль,
+/// но не по API-ключу с правом только на чтение.
+pub struct Writer(pub AuthUser);
+
+impl Deref for Writer {
+    type Target = AuthUser;
+
+    fn deref(&self) -> &AuthUser {
+        &self.0
+    }
+}
+
+impl FromRequestParts<AppState> for Writer {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        let user = AuthUser::from_request_parts(parts, state).await?;
+        user.require(Permission::RecordProgress)?;
+        Ok(Writer(user))
+    }
+}
+
+/// Пользователь с правом редактировать контент (editor или admin).
+pub struct Editor(pub AuthUser);
+
+impl Deref for Editor {
+    type Target = AuthUser;
+
+    fn deref(&self) -> &AuthUser {
+        &self.0
+    }
+}
+
+impl FromRequestParts<AppState> for Editor {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        let user = AuthUser::from_request_parts(parts, state).await?;
+        user.require(Permission::EditContent)?;
+        Ok(Editor(user))
+    }
+}
+
+/// Преподаватель (teacher или admin).
+pub struct Teacher(pub AuthUser);
+
+impl Deref for Teacher {
+    type Target = AuthUser;
+
+    fn deref(&self) -> &AuthUser {
+        &self.0
+    }
+}
+
+impl FromRequestParts<AppState> for Teacher {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        let user = AuthUser::from_request_parts(parts, state).await?;
+        user.require(Permission::ManageClasses)?;
+        Ok(Teacher(user))
+    }
+}
+
+/// Администратор.
+pub struct Admin(pub AuthUser);
+
+impl Deref for Admin {
+    type Target = AuthUser;
+
+    fn deref(&self) -> &AuthUser {
+        &self.0
+    }
+}
+
+impl FromRequestParts<AppState> for Admin {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        let user = AuthUser::from_request_parts(parts, state).await?;
+        user.require(Permission::ManageUsers)?;
+        Ok(Admin(user))
+    }
+}
@@ -0,0 +1,26 @@
+use axum::{
+    routing::{delete, get, patch, post},
+    Router,
+};
+
+use super::api_keys::{create_api_key, get_api_keys, revoke_api_key};
+use super::email::{forgot_password, resend_verification, reset_password, verify_email};
+use super::handlers::{get_info_handler, login, logout, refresh, register, revoke_sessions, update_user_role};
+use crate::lessons::state::AppState;
+
+pub fn auth_router() -> Router<AppState> {
+    Router::new()
+        .route("/register", post(register))
+        .route("/login", post(login))
+        .route("/refresh", post(refresh))
+        .route("/logout", post(logout))
+        .route("/me", get(get_info_handler))
+        .route("/verify-email", post(verify_email))
+        .route("/verify-email/resend", post(resend_verification))
+        .route("/forgot-password", post(forgot_password))
+        .route("/reset-password", post(reset_password))
+        .route("/api-keys", get(get_api_keys).post(create_api_key))
+        .route("/api-keys/{id}", delete(revoke_api_key))
+        .route("/users/{id}/role", patch(update_user_role))
+        .route("/users/{id}/revoke-sessions", post(revoke_sessions))
+}
@@ -1,15 +1,34 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
-use sqlx::types::chrono::NaiveDateTime;
+use sqlx::types::chrono::{DateTime, NaiveDateTime, Utc};
 
 
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "user_role", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    Admin,
+    Editor,
+    Teacher,
+    Student,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "api_key_scope", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ApiKeyScope {
+    Read,
+    Write,
+}
+
 #[derive(Serialize, FromRow)]
 pub struct Users {
     pub id: i32,
     pub username: String,
-    pub password_hash: String,
     pub email: String,
+    pub role: Role,
+    pub email_verified_at: Option<DateTime<Utc>>,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime 
 }
@@ -30,12 +49,83 @@ pub struct LoginInfo {
 
 #[derive(Serialize)]
 pub struct LoginReponse {
-    pub token: String
+    pub token: String,
+    pub refresh_token: String,
+}
+
+#[derive(Deserialize)]
+pub struct RequestRefresh {
+    pub refresh_token: String,
+}
+
+#[derive(Serialize, FromRow)]
+pub struct ApiKey {
+    pub id: i32,
+    pub name: String,
+    pub key_prefix: String,
+    pub scope: ApiKeyScope,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize)]
+pub struct CreatedApiKey {
+    /// Полный ключ показывается только один раз, при создании
+    pub key: String,
+    #[serde(flatten)]
+    pub api_key: ApiKey,
+}
+
+#[derive(Deserialize)]
+pub struct RequestApiKey {
+    pub name: String,
+    pub scope: ApiKeyScope,
+}
+
+#[derive(Deserialize)]
+pub struct RequestEmailToken {
+    pub token: String,
+}
+
+#[derive(Deserialize)]
+pub struct RequestForgotPassword {
+    pub email: String,
+}
+
+#[derive(Deserialize)]
+pub struct RequestResetPassword {
+    pub token: String,
+    pub password: String,
+}
+
+
+#[derive(Deserialize)]
+pub struct RequestRole {
+    pub role: Role,
 }
 
 
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub struct Claims {
     pub sub: String,
+    pub uid: i32,
+    pub role: Role,
+    pub iat: usize,
+    /// Момент выдачи в микросекундах: по нему сравниваем с отзывом сессий,
+    /// секунд из `iat` для этого мало. В токенах, выданных до его появления, поля нет
+    #[serde(default)]
+    pub iat_us: Option<i64>,
     pub exp: usize,
+    pub jti: String,
+}
+
+impl Claims {
+    /// Момент выдачи токена; у старых токенов — с точностью до секунды.
+    pub fn issued_at(&self) -> DateTime<Utc> {
+        self.iat_us
+            .and_then(DateTime::from_timestamp_micros)
+            .or_else(|| DateTime::from_timestamp(self.iat as i64, 0))
+            .unwrap_or_default()
+    }
 }
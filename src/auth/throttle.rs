@@ -0,0 +1,120 @@
+use chrono::{DateTime, Duration, Utc};
+use sqlx::PgPool;
+
+/// Сколько неудачных попыток подряд допускается до блокировки.
+const ACCOUNT_THRESHOLD: i32 = 5;
+const IP_THRESHOLD: i32 = 20;
+
+/// Первая блокировка длится BASE_LOCKOUT_SECS, каждая следующая неудача удваивает её.
+const BASE_LOCKOUT_SECS: i64 = 30;
+const MAX_LOCKOUT_SECS: i64 = 60 * 60;
+
+/// Счётчик сбрасывается, если неудачных попыток не было дольше этого времени.
+const FAILURE_WINDOW_MINUTES: i64 = 15;
+
+/// Имя пользователя берётся как есть: логины сравниваются с учётом регистра,
+/// и блокировка `Alice` не должна затрагивать `alice`.
+fn account_key(username: &str) -> String {
+    format!("user:{}", username)
+}
+
+fn ip_key(ip: &str) -> String {
+    format!("ip:{}", ip)
+}
+
+/// Длительность блокировки после `failures` неудач подряд, если порог уже достигнут.
+pub fn lockout_duration(failures: i32, threshold: i32) -> Option<Duration> {
+    if failures < threshold {
+        return None;
+    }
+
+    let exponent = (failures - threshold).min(16) as u32;
+    let secs = BASE_LOCKOUT_SECS.saturating_mul(2i64.pow(exponent)).min(MAX_LOCKOUT_SECS);
+    Some(Duration::seconds(secs))
+}
+
+/// Если учётная запись или IP сейчас заблокированы, возвращает момент окончания блокировки.
+pub async fn locked_until(db_pool: &PgPool, username: &str, ip: &str) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+    let query = r#"
+        SELECT MAX(locked_until)
+        FROM login_throttle
+        WHERE key IN ($1, $2)
+          AND locked_until > NOW()
+    "#;
+
+    sqlx::query_scalar::<_, Option<DateTime<Utc>>>(query)
+        .bind(account_key(username))
+        .bind(ip_key(ip))
+        .fetch_one(db_pool)
+        .await
+}
+
+async fn register_failure_for_key(db_pool: &PgPool, key: &str, threshold: i32) -> Result<(), sqlx::Error> {
+    let query = r#"
+        INSERT INTO login_throttle (key, failures, last_failure_at)
+        VALUES ($1, 1, NOW())
+        ON CONFLICT (key) DO UPDATE SET
+            failures = CASE
+                WHEN login_throttle.last_failure_at < NOW() - make_interval(mins => $2) THEN 1
+                ELSE login_throttle.failures + 1
+            END,
+            last_failure_at = NOW()
+        RETURNING failures
+    "#;
+
+    let failures = sqlx::query_scalar::<_, i32>(query)
+        .bind(key)
+        .bind(FAILURE_WINDOW_MINUTES as i32)
+        .fetch_one(db_pool)
+        .await?;
+
+    if let Some(duration) = lockout_duration(failures, threshold) {
+        sqlx::query("UPDATE login_throttle SET locked_until = $1 WHERE key = $2")
+            .bind(Utc::now() + duration)
+            .bind(key)
+            .execute(db_pool)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Удаляет записи, которые уже ни на что не влияют: блокировка прошла,
+/// а последняя неудача старше окна, так что счётчик всё равно начался бы заново.
+async fn purge_expired(db_pool: &PgPool) -> Result<(), sqlx::Error> {
+    let query = r#"
+        DELETE FROM login_throttle
+        WHERE last_failure_at < NOW() - make_interval(mins => $1)
+          AND (locked_until IS NULL OR locked_until <= NOW())
+    "#;
+
+    sqlx::query(query)
+        .bind(FAILURE_WINDOW_MINUTES as i32)
+        .execute(db_pool)
+        .await
+        .map(|_| ())
+}
+
+/// Учитывает неудачную попытку для учётной записи и IP и пишет её в журнал.
+pub async fn register_failure(db_pool: &PgPool, username: &str, ip: &str, reason: &str) -> Result<(), sqlx::Error> {
+    sqlx::query("INSERT INTO failed_logins (username, ip, reason) VALUES ($1, $2, $3)")
+        .bind(username)
+        .bind(ip)
+        .bind(reason)
+        .execute(db_pool)
+        .await?;
+
+    purge_expired(db_pool).await?;
+    register_failure_for_key(db_pool, &account_key(username), ACCOUNT_THRESHOLD).await?;
+    register_failure_for_key(db_pool, &ip_key(ip), IP_THRESHOLD).await
+}
+
+/// После успешного входа сбрасываем счётчик учётной записи. Счётчик IP не трогаем,
+/// иначе вход в свой аккаунт позволял бы продолжать перебор чужих.
+pub async fn reset_failures(db_pool: &PgPool, username: &str) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM login_throttle WHERE key = $1")
+        .bind(account_key(username))
+        .execute(db_pool)
+        .await
+        .map(|_| ())
+}
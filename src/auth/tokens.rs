@@ -0,0 +1,147 @@
+use chrono::{DateTime, Duration, Utc};
+use jsonwebtoken::{encode, EncodingKey, Header};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use sqlx::{PgConnection, PgPool};
+
+use crate::auth::extractor::jwt_secret;
+use crate::auth::seralizers::{Claims, Role};
+
+pub const ACCESS_TOKEN_TTL_HOURS: i64 = 1;
+pub const REFRESH_TOKEN_TTL_DAYS: i64 = 30;
+
+/// Случайная строка из `bytes` байт в hex.
+pub fn random_token(bytes: usize) -> String {
+    let mut buf = vec![0u8; bytes];
+    rand::thread_rng().fill_bytes(&mut buf);
+    hex::encode(buf)
+}
+
+/// В базе храним только SHA-256 от токена, сам токен знает лишь клиент.
+pub fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+pub fn issue_access_token(user_id: i32, username: &str, role: Role) -> Result<String, jsonwebtoken::errors::Error> {
+    let now = Utc::now();
+    let claims = Claims {
+        sub: username.to_string(),
+        uid: user_id,
+        role,
+        iat: now.timestamp() as usize,
+        iat_us: Some(now.timestamp_micros()),
+        exp: (now + Duration::hours(ACCESS_TOKEN_TTL_HOURS)).timestamp() as usize,
+        jti: random_token(16),
+    };
+
+    let secret_jwt = jwt_secret();
+    encode(&Header::default(), &claims, &EncodingKey::from_secret(secret_jwt.as_bytes()))
+}
+
+/// Создаёт новый refresh-токен для пользователя. Возвращает id записи и сам токен.
+pub async fn store_refresh_token(conn: &mut PgConnection, user_id: i32) -> Result<(i32, String), sqlx::Error> {
+    let token = random_token(32);
+
+    let query = r#"
+        INSERT INTO refresh_tokens (user_id, token_hash, expires_at)
+        VALUES ($1, $2, $3)
+        RETURNING id
+    "#;
+
+    let id = sqlx::query_scalar::<_, i32>(query)
+        .bind(user_id)
+        .bind(hash_token(&token))
+        .bind(Utc::now() + Duration::days(REFRESH_TOKEN_TTL_DAYS))
+        .fetch_one(conn)
+        .await?;
+
+    Ok((id, token))
+}
+
+/// Токен выдан до массового отзыва сессий. Выданный в ту же микросекунду считается новым.
+fn issued_before_revocation(claims: &Claims, sessions_revoked_at: Option<DateTime<Utc>>) -> bool {
+    sessions_revoked_at.is_some_and(|revoked_at| revoked_at > claims.issued_at())
+}
+
+/// Проверяет, не отозван ли access-токен: по jti или массовым отзывом сессий пользователя.
+pub async fn is_token_revoked(db_pool: &PgPool, claims: &Claims) -> Result<bool, sqlx::Error> {
+    let query = r#"
+        SELECT EXISTS (SELECT 1 FROM revoked_tokens WHERE jti = $1),
+               (SELECT sessions_revoked_at FROM users WHERE id = $2)
+    "#;
+
+    let (jti_revoked, sessions_revoked_at) = sqlx::query_as::<_, (bool, Option<DateTime<Utc>>)>(query)
+        .bind(&claims.jti)
+        .bind(claims.uid)
+        .fetch_one(db_pool)
+        .await?;
+
+    Ok(jti_revoked || issued_before_revocation(claims, sessions_revoked_at))
+}
+
+/// Отзывает все refresh-токены и API-ключи пользователя и делает недействительными выданные ему access-токены.
+/// Ключи тоже отзываются: иначе украденный токен успел бы выпустить ключ, переживающий сброс пароля.
+/// Возвращает `false`, если пользователя не существует.
+pub async fn revoke_user_sessions(conn: &mut PgConnection, user_id: i32) -> Result<bool, sqlx::Error> {
+    sqlx::query("UPDATE refresh_tokens SET revoked_at = NOW() WHERE user_id = $1 AND revoked_at IS NULL")
+        .bind(user_id)
+        .execute(&mut *conn)
+        .await?;
+
+    sqlx::query("UPDATE api_keys SET revoked_at = NOW() WHERE user_id = $1 AND revoked_at IS NULL")
+        .bind(user_id)
+        .execute(&mut *conn)
+        .await?;
+
+    // Время берём из того же источника, что и `iat_us` токенов: NOW() в базе — это начало транзакции
+    let updated = sqlx::query("UPDATE users SET sessions_revoked_at = $1 WHERE id = $2")
+        .bind(Utc::now())
+        .bind(user_id)
+        .execute(&mut *conn)
+        .await?;
+
+    Ok(updated.rows_affected() > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn claims_at(issued_at: DateTime<Utc>) -> Claims {
+        Claims {
+            sub: "user".to_string(),
+            uid: 1,
+            role: Role::Student,
+            iat: issued_at.timestamp() as usize,
+            iat_us: Some(issued_at.timestamp_micros()),
+            exp: (issued_at + Duration::hours(ACCESS_TOKEN_TTL_HOURS)).timestamp() as usize,
+            jti: "jti".to_string(),
+        }
+    }
+
+    fn at(rfc3339: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(rfc3339).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn token_from_earlier_in_the_same_second_is_revoked() {
+        let revoked_at = Some(at("2025-06-01T12:00:00.500Z"));
+        assert!(issued_before_revocation(&claims_at(at("2025-06-01T12:00:00.200Z")), revoked_at));
+    }
+
+    #[test]
+    fn token_issued_after_revocation_stays_valid() {
+        let revoked_at = Some(at("2025-06-01T12:00:00.500Z"));
+        assert!(!issued_before_revocation(&claims_at(at("2025-06-01T12:00:00.800Z")), revoked_at));
+        assert!(!issued_before_revocation(&claims_at(at("2025-06-01T12:00:00.500Z")), revoked_at));
+        assert!(!issued_before_revocation(&claims_at(at("2025-06-01T12:00:00.200Z")), None));
+    }
+
+    #[test]
+    fn legacy_token_without_microseconds_uses_iat() {
+        let mut claims = claims_at(at("2025-06-01T12:00:00.800Z"));
+        claims.iat_us = None;
+        // Без микросекунд токен считается выданным в начале секунды
+        assert!(issued_before_revocation(&claims, Some(at("2025-06-01T12:00:00.500Z"))));
+    }
+}
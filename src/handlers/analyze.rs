@@ -0,0 +1,38 @@
+use axum::response::{IntoResponse, Json as AnswerJson};
+use axum::http::StatusCode;
+
+use crate::arabic::grading::grade_answer;
+use crate::arabic::is_arabic_text;
+use crate::arabic::stemmer::analyze;
+use crate::handlers::query::AnalyzeQuery;
+use crate::lessons::serializers::{AnalyzeResult, RequestAnswerCheck};
+use crate::utils::error::AppError;
+use crate::utils::extract::{Query, ValidatedJson};
+
+/// Предлагает корень и вазн для огласованного слова, ничего не сохраняя.
+/// Если стеммер не справился, `suggestion` будет `null`.
+pub async fn analyze_term(
+    Query(params): Query<AnalyzeQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let term = params.term.trim();
+
+    if !is_arabic_text(term) {
+        return Err(AppError::bad_request("Query parameter term must be written in Arabic script"));
+    }
+
+    let result = AnalyzeResult {
+        term: term.to_string(),
+        suggestion: analyze(term).map(Into::into),
+    };
+
+    Ok((StatusCode::OK, AnswerJson(result)))
+}
+
+/// Сравнивает напечатанный ответ с правильным: балл и расхождения по буквам и огласовкам.
+pub async fn check_answer(
+    ValidatedJson(payload): ValidatedJson<RequestAnswerCheck>,
+) -> Result<impl IntoResponse, AppError> {
+    let grading = grade_answer(&payload.expected, &payload.answer, payload.strictness);
+
+    Ok((StatusCode::OK, AnswerJson(grading)))
+}
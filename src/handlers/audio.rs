@@ -0,0 +1,398 @@
+use axum::body::Body;
+use axum::http::header::{ACCEPT_RANGES, CONTENT_LENGTH, CONTENT_RANGE, CONTENT_TYPE, RANGE};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Json as AnswerJson, Response};
+use axum::extract::State;
+use tokio_util::io::ReaderStream;
+
+use crate::auth::permissions::Editor;
+use crate::auth::tokens::random_token;
+use crate::lessons::serializers::AudioFile;
+use crate::lessons::state::AppState;
+use crate::storage::Storage;
+use crate::utils::error::AppError;
+use crate::utils::extract::{Multipart, Path};
+
+const DEFAULT_AUDIO_MAX_BYTES: usize = 10 * 1024 * 1024;
+
+/// Максимальный размер аудиофайла, AUDIO_MAX_BYTES (по умолчанию 10 МБ).
+pub fn audio_max_bytes() -> usize {
+    std::env::var("AUDIO_MAX_BYTES")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_AUDIO_MAX_BYTES)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AudioFormat {
+    Mp3,
+    Ogg,
+    Wav,
+}
+
+impl AudioFormat {
+    /// Формат по заявленному клиентом Content-Type части запроса.
+    fn from_mime(mime: &str) -> Option<Self> {
+        match mime.split(';').next()?.trim().to_ascii_lowercase().as_str() {
+            "audio/mpeg" | "audio/mp3" => Some(AudioFormat::Mp3),
+            "audio/ogg" | "application/ogg" => Some(AudioFormat::Ogg),
+            "audio/wav" | "audio/x-wav" | "audio/wave" | "audio/vnd.wave" => Some(AudioFormat::Wav),
+            _ => None,
+        }
+    }
+
+    /// Формат по сигнатуре в начале файла.
+    fn sniff(data: &[u8]) -> Option<Self> {
+        if data.starts_with(b"ID3") || (data.len() > 1 && data[0] == 0xFF && data[1] & 0xE0 == 0xE0) {
+            Some(AudioFormat::Mp3)
+        } else if data.starts_with(b"OggS") {
+            Some(AudioFormat::Ogg)
+        } else if data.starts_with(b"RIFF") && data.get(8..12) == Some(b"WAVE".as_slice()) {
+            Some(AudioFormat::Wav)
+        } else {
+            None
+        }
+    }
+
+    fn content_type(&self) -> &'static str {
+        match self {
+            AudioFormat::Mp3 => "audio/mpeg",
+            AudioFormat::Ogg => "audio/ogg",
+            AudioFormat::Wav => "audio/wav",
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            AudioFormat::Mp3 => "mp3",
+            AudioFormat::Ogg => "ogg",
+            AudioFormat::Wav => "wav",
+        }
+    }
+}
+
+/// К чему прикреплено аудио.
+#[derive(Clone, Copy)]
+enum AudioOwner {
+    Word(i32),
+    Lesson(i32),
+}
+
+impl AudioOwner {
+    fn id(&self) -> i32 {
+        match self {
+            AudioOwner::Word(id) | AudioOwner::Lesson(id) => *id,
+        }
+    }
+
+    fn column(&self) -> &'static str {
+        match self {
+            AudioOwner::Word(_) => "word_id",
+            AudioOwner::Lesson(_) => "lesson_id",
+        }
+    }
+
+    fn table(&self) -> &'static str {
+        match self {
+            AudioOwner::Word(_) => "word",
+            AudioOwner::Lesson(_) => "lesson",
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            AudioOwner::Word(_) => "Word",
+            AudioOwner::Lesson(_) => "Lesson",
+        }
+    }
+
+    fn new_key(&self, format: AudioFormat) -> String {
+        format!("{}s/{}/{}.{}", self.table(), self.id(), random_token(8), format.extension())
+    }
+}
+
+/// Читает поле `file` из multipart, не давая ему превысить лимит.
+async fn read_upload(mut multipart: Multipart) -> Result<(Vec<u8>, AudioFormat), AppError> {
+    let max_bytes = audio_max_bytes();
+
+    while let Some(mut field) = multipart.0.next_field().await? {
+        if field.name() != Some("file") {
+            continue;
+        }
+
+        let declared = field
+            .content_type()
+            .and_then(AudioFormat::from_mime)
+            .ok_or_else(|| AppError::UnsupportedMediaType("Only mp3, ogg and wav audio is accepted".to_string()))?;
+
+        let mut data = Vec::new();
+        while let Some(chunk) = field.chunk().await? {
+            if data.len() + chunk.len() > max_bytes {
+                return Err(AppError::PayloadTooLarge(format!("Audio file must be at most {} bytes", max_bytes)));
+            }
+            data.extend_from_slice(&chunk);
+        }
+
+        if data.is_empty() {
+            return Err(AppError::bad_request("Audio file is empty"));
+        }
+
+        if AudioFormat::sniff(&data) != Some(declared) {
+            return Err(AppError::UnsupportedMediaType(format!(
+                "File content is not valid {} audio",
+                declared.extension()
+            )));
+        }
+
+        return Ok((data, declared));
+    }
+
+    Err(AppError::bad_request("Multipart field `file` is required"))
+}
+
+async fn ensure_owner_exists(state: &AppState, owner: AudioOwner) -> Result<(), AppError> {
+    let query = format!("SELECT EXISTS (SELECT 1 FROM {} WHERE id = $1)", owner.table());
+
+    let exists = sqlx::query_scalar::<_, bool>(&query)
+        .bind(owner.id())
+        .fetch_one(&state.db_pool)
+        .await?;
+
+    if !exists {
+        return Err(AppError::not_found(format!("{} with id {} not found", owner.name(), owner.id())));
+    }
+    Ok(())
+}
+
+/// Удаляет файлы из хранилища. Ошибки только логируются: запись в базе уже удалена.
+pub async fn remove_stored_files(storage: &dyn Storage, keys: &[String]) {
+    for key in keys {
+        if let Err(err) = storage.delete(key).await {
+            eprintln!("Failed to delete stored file {}: {}", key, err);
+        }
+    }
+}
+
+async fn upload_audio(state: &AppState, owner: AudioOwner, multipart: Multipart) -> Result<AudioFile, AppError> {
+    ensure_owner_exists(state, owner).await?;
+
+    let (data, format) = read_upload(multipart).await?;
+    let key = owner.new_key(format);
+
+    state
+        .storage
+        .put(&key, &data)
+        .await
+        .map_err(|e| AppError::internal("Failed to store audio", e))?;
+
+    let saved = save_audio_record(state, owner, &key, format, data.len() as i64).await;
+
+    match saved {
+        Ok((audio, old_key)) => {
+            if let Some(old_key) = old_key {
+                remove_stored_files(state.storage.as_ref(), &[old_key]).await;
+            }
+            Ok(audio)
+        }
+        Err(err) => {
+            remove_stored_files(state.storage.as_ref(), &[key]).await;
+            Err(err)
+        }
+    }
+}
+
+/// Создаёт или заменяет запись об аудио. Возвращает её и ключ прежнего файла, если он был.
+async fn save_audio_record(
+    state: &AppState,
+    owner: AudioOwner,
+    key: &str,
+    format: AudioFormat,
+    size: i64,
+) -> Result<(AudioFile, Option<String>), AppError> {
+    let mut tx = state.db_pool.begin().await?;
+
+    let old_key = sqlx::query_scalar::<_, String>(&format!(
+        "SELECT storage_key FROM audio_file WHERE {} = $1 FOR UPDATE",
+        owner.column()
+    ))
+    .bind(owner.id())
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let query = format!(
+        r#"
+        INSERT INTO audio_file ({column}, storage_key, content_type, size)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT ({column}) DO UPDATE
+            SET storage_key = EXCLUDED.storage_key,
+                content_type = EXCLUDED.content_type,
+                size = EXCLUDED.size,
+                created_at = CURRENT_TIMESTAMP
+        RETURNING content_type, size, created_at
+        "#,
+        column = owner.column()
+    );
+
+    let audio = sqlx::query_as::<_, AudioFile>(&query)
+        .bind(owner.id())
+        .bind(key)
+        .bind(format.content_type())
+        .bind(size)
+        .fetch_one(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok((audio, old_key))
+}
+
+/// Разбирает заголовок Range вида `bytes=a-b`, `bytes=a-` или `bytes=-n`.
+/// Несколько диапазонов и неизвестные единицы игнорируются (отдаём файл целиком),
+/// `Err(())` — диапазон не пересекается с файлом.
+fn parse_range(header: &str, size: u64) -> Option<Result<(u64, u64), ()>> {
+    let spec = header.trim().strip_prefix("bytes=")?;
+    if spec.contains(',') {
+        return None;
+    }
+
+    let (start, end) = spec.split_once('-')?;
+    let (start, end) = (start.trim(), end.trim());
+
+    let range = if start.is_empty() {
+        let suffix: u64 = end.parse().ok()?;
+        if suffix == 0 {
+            return Some(Err(()));
+        }
+        (size.saturating_sub(suffix), size - 1)
+    } else {
+        let start: u64 = start.parse().ok()?;
+        let end = if end.is_empty() { size - 1 } else { end.parse::<u64>().ok()?.min(size - 1) };
+        if start > end || start >= size {
+            return Some(Err(()));
+        }
+        (start, end)
+    };
+
+    Some(Ok(range))
+}
+
+async fn download_audio(state: &AppState, owner: AudioOwner, headers: &HeaderMap) -> Result<Response, AppError> {
+    let record = sqlx::query_as::<_, (String, String, i64)>(&format!(
+        "SELECT storage_key, content_type, size FROM audio_file WHERE {} = $1",
+        owner.column()
+    ))
+    .bind(owner.id())
+    .fetch_optional(&state.db_pool)
+    .await?;
+
+    let Some((key, content_type, size)) = record else {
+        return Err(AppError::not_found(format!("{} {} has no audio", owner.name(), owner.id())));
+    };
+    let size = size as u64;
+
+    let range = headers
+        .get(RANGE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| parse_range(value, size));
+
+    let (status, start, end) = match range {
+        None => (StatusCode::OK, 0, size - 1),
+        Some(Ok((start, end))) => (StatusCode::PARTIAL_CONTENT, start, end),
+        Some(Err(())) => {
+            return Ok((
+                StatusCode::RANGE_NOT_SATISFIABLE,
+                [(CONTENT_RANGE, format!("bytes */{}", size))],
+            )
+                .into_response());
+        }
+    };
+
+    let reader = state
+        .storage
+        .read(&key, start..end + 1)
+        .await
+        .map_err(|e| AppError::internal("Failed to read audio", e))?;
+
+    let mut response = Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, content_type)
+        .header(ACCEPT_RANGES, "bytes")
+        .header(CONTENT_LENGTH, end - start + 1);
+
+    if status == StatusCode::PARTIAL_CONTENT {
+        response = response.header(CONTENT_RANGE, format!("bytes {}-{}/{}", start, end, size));
+    }
+
+    response
+        .body(Body::from_stream(ReaderStream::new(reader)))
+        .map_err(|e| AppError::internal("Failed to build audio response", e))
+}
+
+async fn delete_audio(state: &AppState, owner: AudioOwner) -> Result<StatusCode, AppError> {
+    let key = sqlx::query_scalar::<_, String>(&format!(
+        "DELETE FROM audio_file WHERE {} = $1 RETURNING storage_key",
+        owner.column()
+    ))
+    .bind(owner.id())
+    .fetch_optional(&state.db_pool)
+    .await?
+    .ok_or_else(|| AppError::not_found(format!("{} {} has no audio", owner.name(), owner.id())))?;
+
+    remove_stored_files(state.storage.as_ref(), &[key]).await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Загрузка произношения: multipart с полем `file` (mp3, ogg или wav).
+pub async fn upload_word_audio(
+    State(state): State<AppState>,
+    _user: Editor,
+    Path(id): Path<i32>,
+    multipart: Multipart,
+) -> Result<impl IntoResponse, AppError> {
+    let audio = upload_audio(&state, AudioOwner::Word(id), multipart).await?;
+    Ok((StatusCode::CREATED, AnswerJson(audio)))
+}
+
+/// Отдаёт аудио потоком, поддерживает Range для перемотки в плеере.
+pub async fn get_word_audio(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    download_audio(&state, AudioOwner::Word(id), &headers).await
+}
+
+pub async fn delete_word_audio(
+    State(state): State<AppState>,
+    _user: Editor,
+    Path(id): Path<i32>,
+) -> Result<impl IntoResponse, AppError> {
+    delete_audio(&state, AudioOwner::Word(id)).await
+}
+
+pub async fn upload_lesson_audio(
+    State(state): State<AppState>,
+    _user: Editor,
+    Path(id): Path<i32>,
+    multipart: Multipart,
+) -> Result<impl IntoResponse, AppError> {
+    let audio = upload_audio(&state, AudioOwner::Lesson(id), multipart).await?;
+    Ok((StatusCode::CREATED, AnswerJson(audio)))
+}
+
+pub async fn get_lesson_audio(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    download_audio(&state, AudioOwner::Lesson(id), &headers).await
+}
+
+pub async fn delete_lesson_audio(
+    State(state): State<AppState>,
+    _user: Editor,
+    Path(id): Path<i32>,
+) -> Result<impl IntoResponse, AppError> {
+    delete_audio(&state, AudioOwner::Lesson(id)).await
+}
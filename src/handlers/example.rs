@@ -0,0 +1,145 @@
+use axum::response::{IntoResponse, Json as AnswerJson};
+use axum::{extract::State, http::StatusCode};
+use serde_json::json;
+use sqlx::PgPool;
+
+use crate::arabic::normalize::normalize;
+use crate::auth::permissions::Editor;
+use crate::lessons::serializers::{RequestExample, WordExample};
+use crate::lessons::state::AppState;
+use crate::utils::error::AppError;
+use crate::utils::extract::{Path, ValidatedJson};
+
+async fn ensure_word_exists(db_pool: &PgPool, word_id: i32) -> Result<(), AppError> {
+    let exists = sqlx::query_scalar::<_, bool>("SELECT EXISTS (SELECT 1 FROM word WHERE id = $1)")
+        .bind(word_id)
+        .fetch_one(db_pool)
+        .await?;
+
+    if !exists {
+        return Err(AppError::not_found(format!("Word with id {} not found", word_id)));
+    }
+    Ok(())
+}
+
+/// Пример можно привязать только к уроку, в тексте которого он действительно есть
+/// (сравнение без учёта огласовок).
+async fn check_lesson_link(db_pool: &PgPool, payload: &RequestExample) -> Result<(), AppError> {
+    let Some(lesson_id) = payload.lesson_id else {
+        return Ok(());
+    };
+
+    let text = sqlx::query_scalar::<_, String>("SELECT text FROM lesson WHERE id = $1")
+        .bind(lesson_id)
+        .fetch_optional(db_pool)
+        .await?;
+
+    let message = match text {
+        None => format!("Lesson with id {} not found", lesson_id),
+        Some(text) if !normalize(&text).contains(normalize(payload.text.trim()).as_str()) => {
+            "sentence does not appear in the lesson text".to_string()
+        }
+        Some(_) => return Ok(()),
+    };
+
+    Err(AppError::unprocessable("Validation failed", json!({ "lesson_id": [message] })))
+}
+
+pub async fn get_word_examples(
+    State(state): State<AppState>,
+    Path(word_id): Path<i32>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_word_exists(&state.db_pool, word_id).await?;
+
+    let examples = sqlx::query_as::<_, WordExample>("SELECT * FROM word_example WHERE word_id = $1 ORDER BY id")
+        .bind(word_id)
+        .fetch_all(&state.db_pool)
+        .await?;
+
+    Ok((StatusCode::OK, AnswerJson(examples)))
+}
+
+pub async fn get_word_example(
+    State(state): State<AppState>,
+    Path((word_id, example_id)): Path<(i32, i32)>,
+) -> Result<impl IntoResponse, AppError> {
+    let example = sqlx::query_as::<_, WordExample>("SELECT * FROM word_example WHERE id = $1 AND word_id = $2")
+        .bind(example_id)
+        .bind(word_id)
+        .fetch_optional(&state.db_pool)
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Example with id {} not found", example_id)))?;
+
+    Ok(AnswerJson(example))
+}
+
+pub async fn create_word_example(
+    State(state): State<AppState>,
+    _user: Editor,
+    Path(word_id): Path<i32>,
+    ValidatedJson(payload): ValidatedJson<RequestExample>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_word_exists(&state.db_pool, word_id).await?;
+    check_lesson_link(&state.db_pool, &payload).await?;
+
+    let query = r#"
+        INSERT INTO word_example (word_id, text, translation, source, lesson_id)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING id, word_id, text, translation, source, lesson_id, created_at
+    "#;
+
+    let example = sqlx::query_as::<_, WordExample>(query)
+        .bind(word_id)
+        .bind(payload.text.trim())
+        .bind(&payload.translation)
+        .bind(&payload.source)
+        .bind(payload.lesson_id)
+        .fetch_one(&state.db_pool)
+        .await?;
+
+    Ok((StatusCode::CREATED, AnswerJson(example)))
+}
+
+pub async fn update_word_example(
+    State(state): State<AppState>,
+    _user: Editor,
+    Path((word_id, example_id)): Path<(i32, i32)>,
+    ValidatedJson(payload): ValidatedJson<RequestExample>,
+) -> Result<impl IntoResponse, AppError> {
+    check_lesson_link(&state.db_pool, &payload).await?;
+
+    let query = r#"
+        UPDATE word_example
+        SET text = $1, translation = $2, source = $3, lesson_id = $4
+        WHERE id = $5 AND word_id = $6
+        RETURNING id, word_id, text, translation, source, lesson_id, created_at
+    "#;
+
+    let example = sqlx::query_as::<_, WordExample>(query)
+        .bind(payload.text.trim())
+        .bind(&payload.translation)
+        .bind(&payload.source)
+        .bind(payload.lesson_id)
+        .bind(example_id)
+        .bind(word_id)
+        .fetch_optional(&state.db_pool)
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Example with id {} not found", example_id)))?;
+
+    Ok(AnswerJson(example))
+}
+
+pub async fn delete_word_example(
+    State(state): State<AppState>,
+    _user: Editor,
+    Path((word_id, example_id)): Path<(i32, i32)>,
+) -> Result<impl IntoResponse, AppError> {
+    sqlx::query("DELETE FROM word_example WHERE id = $1 AND word_id = $2 RETURNING id")
+        .bind(example_id)
+        .bind(word_id)
+        .fetch_optional(&state.db_pool)
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Example with id {} not found", example_id)))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
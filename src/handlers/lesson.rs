@@ -1,18 +1,33 @@
-use std::thread::Builder;
+use std::cmp::Reverse;
 
 use axum::response::{IntoResponse, Json as AnswerJson};
-use axum::{
-    extract::{Json, Path, Query, State},
-    http::StatusCode,
-};
+use axum::{extract::State, http::StatusCode};
 use serde::Deserialize;
+use serde_json::json;
 use sqlx::QueryBuilder;
 
-use crate::lessons::serializers::{Lesson, NewWord, PatchLesson, RequestLesson, Word};
+use crate::arabic::root::canonical_root;
+use crate::arabic::normalize::normalize;
+use crate::arabic::stemmer::analyze;
+use crate::arabic::tokenize::{strip_article, tokenize, Lexicon, TokenKind};
+use crate::lessons::serializers::{
+    AnnotatedLesson, AnnotatedToken, CreatedWord, Lesson, LessonDetail, LessonSearchResult, NewWord, PatchLesson,
+    RequestLesson, RequestLessonOrder, Transliterated, UnknownWord, Word,
+};
+use crate::auth::extractor::AuthUser;
+use crate::auth::permissions::Editor;
+use crate::handlers::audio::remove_stored_files;
+use crate::handlers::progress::attach_progress;
+use crate::handlers::query::TransliterationQuery;
+use crate::handlers::translation::{attach_definitions, collect_translations, save_translations};
 use crate::lessons::state::AppState;
+use crate::utils::error::AppError;
+use crate::utils::extract::{Path, Query, ValidatedJson};
+use crate::utils::lang::Lang;
 use crate::utils::pagination::{HasPagination, PaginateQuery, PaginateResult};
 
 impl PaginateQuery for Lesson {}
+impl PaginateQuery for LessonSearchResult {}
 
 #[derive(Deserialize)]
 pub struct LessonQuery {
@@ -31,24 +46,44 @@ impl HasPagination for LessonQuery {
     }
 }
 
+#[derive(Deserialize)]
+pub struct LessonSearchQuery {
+    pub q: String,
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+    pub textbook_id: Option<i32>,
+}
+
+impl HasPagination for LessonSearchQuery {
+    fn page(&self) -> Option<i64> {
+        self.page
+    }
+
+    fn limit(&self) -> Option<i64> {
+        self.limit
+    }
+}
+
 #[axum::debug_handler]
 pub async fn get_lessons(
     State(state): State<AppState>,
     Query(params): Query<LessonQuery>,
-) -> impl IntoResponse {
+    Query(translit): Query<TransliterationQuery>,
+    user: Option<AuthUser>,
+) -> Result<impl IntoResponse, AppError> {
     let mut builder = QueryBuilder::new("SELECT * FROM lesson WHERE 1=1");
 
     if let Some(id) = params.textbook_id {
         builder.push(" AND textbook_id = ").push_bind(id);
     }
 
-    builder.push(" ORDER BY id");
+    builder.push(" ORDER BY textbook_id, position");
 
-    let lesson_result = Lesson::paginate_query(&state.db_pool, builder, &params).await;
+    match Lesson::paginate_query(&state.db_pool, builder, &params).await? {
+        PaginateResult::Success(mut recods) => {
+            attach_progress(&state.db_pool, &mut recods, user.as_ref()).await?;
 
-    match lesson_result {
-        Ok(PaginateResult::Success(recods)) => {
-            let mut response = AnswerJson(recods).into_response();
+            let mut response = AnswerJson(Transliterated::all(recods, translit.transliteration)).into_response();
 
             let total_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM lesson")
                 .fetch_one(&state.db_pool)
@@ -57,73 +92,196 @@ pub async fn get_lessons(
 
             response = Lesson::add_pagination_headers(response, total_count, &params);
 
-            response
-        }
-        Ok(PaginateResult::NotFound) => StatusCode::NOT_FOUND.into_response(),
-        Err(err) => {
-            eprintln!("DB error: {:?}", err);
-            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+            Ok(response)
         }
+        PaginateResult::NotFound => Err(AppError::not_found("Page not found")),
     }
 }
 
-pub async fn get_leson(State(state): State<AppState>, Path(id): Path<i32>) -> impl IntoResponse {
+/// Полнотекстовый поиск по названию и тексту уроков (без учёта огласовок).
+/// Результаты отсортированы по релевантности, совпадения в названии весят больше.
+pub async fn search_lessons(
+    State(state): State<AppState>,
+    Query(params): Query<LessonSearchQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    if params.q.trim().is_empty() {
+        return Err(AppError::bad_request("Query parameter q must not be empty"));
+    }
+
+    let mut builder = QueryBuilder::new(
+        r#"
+        SELECT id, title, textbook_id,
+               ts_rank(search_vector, query) AS rank,
+               ts_headline('simple', arabic_normalize(text), query,
+                           'StartSel=<mark>, StopSel=</mark>, MaxFragments=2, MaxWords=20, MinWords=5') AS snippet
+        FROM lesson, plainto_tsquery('simple', arabic_normalize("#,
+    );
+    builder.push_bind(params.q.trim().to_string());
+    builder.push(")) AS query WHERE search_vector @@ query");
+
+    if let Some(id) = params.textbook_id {
+        builder.push(" AND textbook_id = ").push_bind(id);
+    }
+
+    builder.push(" ORDER BY rank DESC, id");
+
+    match LessonSearchResult::paginate_query(&state.db_pool, builder, &params).await? {
+        PaginateResult::Success(results) => Ok((StatusCode::OK, AnswerJson(results))),
+        PaginateResult::NotFound => Err(AppError::not_found("Page not found")),
+    }
+}
+
+pub async fn get_leson(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    Query(translit): Query<TransliterationQuery>,
+    user: Option<AuthUser>,
+) -> Result<impl IntoResponse, AppError> {
     let query = r#"
-            SELECT * FROM lesson
-            WHERE id = $1
-        "#;
+        SELECT l.*,
+            (SELECT p.id FROM lesson p
+             WHERE p.textbook_id = l.textbook_id AND p.position < l.position
+             ORDER BY p.position DESC LIMIT 1) AS prev,
+            (SELECT n.id FROM lesson n
+             WHERE n.textbook_id = l.textbook_id AND n.position > l.position
+             ORDER BY n.position LIMIT 1) AS next
+        FROM lesson l
+        WHERE l.id = $1
+    "#;
+
+    let mut lesson = sqlx::query_as::<_, LessonDetail>(query)
+        .bind(id)
+        .fetch_optional(&state.db_pool)
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Lesson with id {} not found", id)))?;
 
-    let result = sqlx::query_as::<_, Lesson>(query)
+    attach_progress(&state.db_pool, std::slice::from_mut(&mut lesson.lesson), user.as_ref()).await?;
+
+    Ok((StatusCode::OK, AnswerJson(Transliterated::new(lesson, translit.transliteration))))
+}
+
+/// Текст урока, разбитый на токены; арабские слова сопоставлены со словами урока
+/// без учёта огласовок, артикля и слитных предлогов/местоимений (وَالْكِتَابُ -> كتاب).
+pub async fn get_annotated_lesson(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<impl IntoResponse, AppError> {
+    let text = sqlx::query_scalar::<_, String>("SELECT text FROM lesson WHERE id = $1")
         .bind(id)
         .fetch_optional(&state.db_pool)
-        .await;
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Lesson with id {} not found", id)))?;
 
-    match result {
-        Ok(Some(result)) => (StatusCode::OK, AnswerJson(result)).into_response(),
-        Ok(None) => StatusCode::NOT_FOUND.into_response(),
-        Err(_) => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    let words = sqlx::query_as::<_, (i32, String)>("SELECT id, term FROM word WHERE lesson_id = $1 ORDER BY id")
+        .bind(id)
+        .fetch_all(&state.db_pool)
+        .await?;
+
+    let mut lexicon = Lexicon::new();
+    for (word_id, term) in &words {
+        lexicon.insert(term, *word_id);
     }
+
+    let tokens = tokenize(&text)
+        .into_iter()
+        .map(|token| {
+            let is_word = token.kind == TokenKind::Word;
+            let word_ids = if is_word {
+                lexicon.lookup(&token.text).map(<[i32]>::to_vec).unwrap_or_default()
+            } else {
+                Vec::new()
+            };
+            AnnotatedToken { text: token.text, is_word, word_ids }
+        })
+        .collect();
+
+    Ok((StatusCode::OK, AnswerJson(AnnotatedLesson { lesson_id: id, tokens })))
+}
+
+/// Слова текста, которые не покрыты словами этого урока и предыдущих уроков того же учебника,
+/// с числом вхождений. Сначала самые частые.
+pub async fn get_unknown_words(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<impl IntoResponse, AppError> {
+    let text = sqlx::query_scalar::<_, String>("SELECT text FROM lesson WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&state.db_pool)
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Lesson with id {} not found", id)))?;
+
+    let query = r#"
+        SELECT w.id, w.term FROM word w
+        JOIN lesson l ON l.id = w.lesson_id
+        JOIN lesson cur ON cur.id = $1
+        WHERE l.id = cur.id OR (l.textbook_id = cur.textbook_id AND l.position < cur.position)
+    "#;
+
+    let words = sqlx::query_as::<_, (i32, String)>(query)
+        .bind(id)
+        .fetch_all(&state.db_pool)
+        .await?;
+
+    let mut lexicon = Lexicon::new();
+    for (word_id, term) in &words {
+        lexicon.insert(term, *word_id);
+    }
+
+    let mut unknown: Vec<UnknownWord> = Vec::new();
+    for token in tokenize(&text) {
+        if token.kind != TokenKind::Word || lexicon.lookup(&token.text).is_some() {
+            continue;
+        }
+
+        let normalized = strip_article(&normalize(&token.text)).to_string();
+        match unknown.iter_mut().find(|w| w.normalized == normalized) {
+            Some(word) => word.count += 1,
+            None => unknown.push(UnknownWord { text: token.text, normalized, count: 1 }),
+        }
+    }
+
+    // Сортировка устойчивая: при равной частоте сохраняется порядок появления в тексте
+    unknown.sort_by_key(|word| Reverse(word.count));
+
+    Ok((StatusCode::OK, AnswerJson(unknown)))
 }
 
 pub async fn create_lesson(
     State(state): State<AppState>,
-    Json(payload): Json<RequestLesson>,
-) -> impl IntoResponse {
+    _user: Editor,
+    ValidatedJson(payload): ValidatedJson<RequestLesson>,
+) -> Result<impl IntoResponse, AppError> {
+    // Новый урок встаёт в конец учебника
     let query = r#"
-        INSERT INTO lesson (title, text, video_url, textbook_id)
-        VALUES ($1, $2, $3, $4)
-        RETURNING id, title, text, video_url, created_at, textbook_id
+        INSERT INTO lesson (title, text, video_url, textbook_id, position)
+        VALUES ($1, $2, $3, $4, (SELECT COALESCE(MAX(position), 0) + 1 FROM lesson WHERE textbook_id = $4))
+        RETURNING id, title, text, video_url, created_at, textbook_id, position
     "#;
 
-    let result = sqlx::query_as::<_, Lesson>(query)
+    let lesson = sqlx::query_as::<_, Lesson>(query)
         .bind(&payload.title)
         .bind(&payload.text)
         .bind(&payload.video_url)
-        .bind(&payload.textbook_id)
+        .bind(payload.textbook_id)
         .fetch_one(&state.db_pool)
-        .await;
+        .await?;
 
-    match result {
-        Ok(result) => (StatusCode::CREATED, AnswerJson(result)).into_response(),
-        Err(err) => {
-            eprint!("Failed to create lesson: {:?}", err);
-            StatusCode::INTERNAL_SERVER_ERROR.into_response()
-        }
-    }
+    Ok((StatusCode::CREATED, AnswerJson(lesson)))
 }
 
 pub async fn update_lesson_patch(
     Path(lesson_id): Path<i32>,
     State(state): State<AppState>,
-    Json(payload): Json<PatchLesson>,
-) -> impl IntoResponse {
+    _user: Editor,
+    ValidatedJson(payload): ValidatedJson<PatchLesson>,
+) -> Result<impl IntoResponse, AppError> {
     // Проверка наличия полей для обновления
     if payload.title.is_none()
         && payload.text.is_none()
         && payload.video_url.is_none()
         && payload.textbook_id.is_none()
     {
-        return Err((StatusCode::BAD_REQUEST, "No fields to update".to_string()));
+        return Err(AppError::bad_request("No fields to update"));
     }
 
     let query = r#"
@@ -131,113 +289,191 @@ pub async fn update_lesson_patch(
             title = COALESCE($1, title),
             text = COALESCE($2, text),
             video_url = COALESCE($3, video_url),
-            textbook_id = COALESCE($4, textbook_id)
+            textbook_id = COALESCE($4, textbook_id),
+            -- при переносе в другой учебник урок становится в его конец
+            position = CASE
+                WHEN $4 IS NOT NULL AND $4 <> textbook_id
+                THEN (SELECT COALESCE(MAX(position), 0) + 1 FROM lesson WHERE textbook_id = $4)
+                ELSE position
+            END
         WHERE id = $5
-        RETURNING id, title, text, video_url, created_at, textbook_id
+        RETURNING id, title, text, video_url, created_at, textbook_id, position
         "#;
 
-    let result = sqlx::query_as::<_, Lesson>(query)
+    let lesson = sqlx::query_as::<_, Lesson>(query)
         .bind(&payload.title)
         .bind(&payload.text)
         .bind(&payload.video_url)
-        .bind(&payload.textbook_id)
-        .bind(&lesson_id)
+        .bind(payload.textbook_id)
+        .bind(lesson_id)
         .fetch_optional(&state.db_pool)
-        .await;
-
-    match result {
-        Ok(Some(lesson)) => Ok(AnswerJson(lesson)),
-        Ok(None) => Err((
-            StatusCode::NOT_FOUND,
-            format!("Lesson with id {} not found", lesson_id),
-        )),
-        Err(err) => {
-            eprintln!("Failed to update lesson: {:?}", err);
-            Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "Failed to update lesson".to_string(),
-            ))
-        }
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Lesson with id {} not found", lesson_id)))?;
+
+    Ok(AnswerJson(lesson))
+}
+
+/// Задаёт порядок уроков учебника. Нужно передать все его уроки:
+/// частичный список не даёт однозначного порядка для остальных.
+pub async fn reorder_lessons(
+    State(state): State<AppState>,
+    _user: Editor,
+    Path(textbook_id): Path<i32>,
+    ValidatedJson(payload): ValidatedJson<RequestLessonOrder>,
+) -> Result<impl IntoResponse, AppError> {
+    let mut tx = state.db_pool.begin().await?;
+
+    let textbook = sqlx::query_scalar::<_, i32>("SELECT id FROM textbook WHERE id = $1 FOR UPDATE")
+        .bind(textbook_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+    if textbook.is_none() {
+        return Err(AppError::not_found(format!("Textbook with id {} not found", textbook_id)));
+    }
+
+    let mut current = sqlx::query_scalar::<_, i32>("SELECT id FROM lesson WHERE textbook_id = $1 FOR UPDATE")
+        .bind(textbook_id)
+        .fetch_all(&mut *tx)
+        .await?;
+
+    let mut requested = payload.lesson_ids.clone();
+    current.sort_unstable();
+    requested.sort_unstable();
+
+    if current != requested {
+        return Err(AppError::unprocessable(
+            "Validation failed",
+            json!({ "lesson_ids": ["must list every lesson of the textbook exactly once"] }),
+        ));
     }
+
+    let query = r#"
+        UPDATE lesson SET position = ordered.position
+        FROM UNNEST($1::INTEGER[]) WITH ORDINALITY AS ordered(id, position)
+        WHERE lesson.id = ordered.id
+    "#;
+
+    sqlx::query(query)
+        .bind(&payload.lesson_ids)
+        .execute(&mut *tx)
+        .await?;
+
+    let lessons = sqlx::query_as::<_, Lesson>("SELECT * FROM lesson WHERE textbook_id = $1 ORDER BY position")
+        .bind(textbook_id)
+        .fetch_all(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok((StatusCode::OK, AnswerJson(lessons)))
 }
 
 pub async fn delete_lesson(
     State(state): State<AppState>,
+    _user: Editor,
     Path(id): Path<i32>,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, AppError> {
     let query = r#"
         DELETE FROM lesson
         WHERE id = $1
         RETURNING id
     "#;
 
-    let result = sqlx::query(query)
+    let mut tx = state.db_pool.begin().await?;
+
+    // Аудио урока и его слов удалится из базы каскадом, файлы убираем из хранилища сами
+    let audio_keys = sqlx::query_scalar::<_, String>(
+        r#"
+        SELECT storage_key FROM audio_file
+        WHERE lesson_id = $1
+           OR word_id IN (SELECT id FROM word WHERE lesson_id = $1)
+        "#,
+    )
+    .bind(id)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    sqlx::query(query)
         .bind(id)
-        .fetch_optional(&state.db_pool)
-        .await;
-
-    match result {
-        Ok(Some(_)) => StatusCode::NO_CONTENT.into_response(),
-        Ok(None) => StatusCode::NOT_FOUND.into_response(),
-        Err(err) => {
-            eprint!("Failed to delete lesson {:?}", err);
-            StatusCode::INTERNAL_SERVER_ERROR.into_response()
-        }
-    }
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| AppError::from_delete(e, "Lesson"))?
+        .ok_or_else(|| AppError::not_found(format!("Lesson with id {} not found", id)))?;
+
+    tx.commit().await?;
+
+    remove_stored_files(state.storage.as_ref(), &audio_keys).await;
+
+    Ok(StatusCode::NO_CONTENT)
 }
 
 pub async fn get_all_word_for_lesson(
     State(state): State<AppState>,
     Path(id): Path<i32>,
-) -> impl IntoResponse {
+    Query(translit): Query<TransliterationQuery>,
+    lang: Lang,
+) -> Result<impl IntoResponse, AppError> {
     let query = r#"
         SELECT * FROM word
         WHERE lesson_id = $1
     "#;
 
-    let result = sqlx::query_as::<_, Word>(query)
+    let mut words = sqlx::query_as::<_, Word>(query)
         .bind(id)
         .fetch_all(&state.db_pool)
-        .await;
+        .await?;
 
-    match result {
-        Ok(words) => {
-            if words.is_empty() {
-                StatusCode::NOT_FOUND.into_response()
-            } else {
-                (StatusCode::OK, AnswerJson(words)).into_response()
-            }
-        }
-        Err(err) => {
-            eprintln!("Failed to fetch words: {:?}", err);
-            StatusCode::INTERNAL_SERVER_ERROR.into_response()
-        }
+    if words.is_empty() {
+        return Err(AppError::not_found(format!("No words found for lesson {}", id)));
     }
+
+    attach_definitions(&state.db_pool, &mut words, &lang).await?;
+
+    Ok((StatusCode::OK, AnswerJson(Transliterated::all(words, translit.transliteration))))
 }
 
+/// Кроме `definition` можно сразу передать переводы на несколько языков в `translations`.
 pub async fn add_word_to_lesson(
     State(state): State<AppState>,
+    _user: Editor,
     Path(lesson_id): Path<i32>,
-    Json(payload): Json<NewWord>,
-) -> impl IntoResponse {
+    lang: Lang,
+    ValidatedJson(payload): ValidatedJson<NewWord>,
+) -> Result<impl IntoResponse, AppError> {
     let query = r#"
-        INSERT INTO word (term, definition, lesson_id)
-        VALUES ($1, $2, $3)
-        RETURNING id, term, definition, lesson_id
+        INSERT INTO word (term, lesson_id, root, pattern,
+                          part_of_speech, gender, dual, plural, plural_type, past, present, masdar)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+        RETURNING id, term, lesson_id, root, pattern,
+                  part_of_speech, gender, dual, plural, plural_type, past, present, masdar
     "#;
 
-    let result = sqlx::query_as::<_, Word>(query)
+    let mut tx = state.db_pool.begin().await?;
+
+    let mut word = sqlx::query_as::<_, Word>(query)
         .bind(&payload.term)
-        .bind(&payload.definition)
         .bind(lesson_id)
-        .fetch_one(&state.db_pool)
-        .await;
+        .bind(payload.root.as_deref().and_then(canonical_root))
+        .bind(&payload.pattern)
+        .bind(payload.part_of_speech)
+        .bind(payload.gender)
+        .bind(&payload.dual)
+        .bind(&payload.plural)
+        .bind(payload.plural_type)
+        .bind(&payload.past)
+        .bind(&payload.present)
+        .bind(&payload.masdar)
+        .fetch_one(&mut *tx)
+        .await?;
 
-    match result {
-        Ok(word) => (StatusCode::CREATED, AnswerJson(word)).into_response(),
-        Err(err) => {
-            eprintln!("Failed to insert word: {:?}", err);
-            StatusCode::INTERNAL_SERVER_ERROR.into_response()
-        }
-    }
+    save_translations(&mut tx, word.id, &collect_translations(&payload.definition, &payload.translations)).await?;
+
+    tx.commit().await?;
+
+    attach_definitions(&state.db_pool, std::slice::from_mut(&mut word), &lang).await?;
+
+    let suggestion = analyze(&word.term).map(Into::into);
+
+    Ok((StatusCode::CREATED, AnswerJson(CreatedWord { word, suggestion })))
 }
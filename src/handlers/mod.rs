@@ -1,4 +1,13 @@
+pub mod analyze;
+pub mod audio;
+pub mod example;
 pub mod lesson;
+pub mod progress;
 pub mod query;
+pub mod quiz;
+pub mod review;
+pub mod root;
 pub mod textbook;
+pub mod translation;
+pub mod transliterate;
 pub mod word;
@@ -0,0 +1,198 @@
+use std::collections::HashSet;
+
+use axum::response::{IntoResponse, Json as AnswerJson};
+use axum::{extract::State, http::StatusCode};
+use sqlx::PgPool;
+
+use crate::auth::extractor::AuthUser;
+use crate::auth::permissions::{Teacher, Writer};
+use crate::lessons::serializers::{Lesson, LessonProgress, MyProgress, RequestProgress, TextbookProgress};
+use crate::lessons::state::AppState;
+use crate::utils::error::AppError;
+use crate::utils::extract::{Path, ValidatedJson};
+
+const LESSON_PROGRESS_COLUMNS: &str = r#"
+    p.lesson_id, l.title AS lesson_title, l.textbook_id, p.started_at, p.completed_at,
+    p.time_spent_seconds, p.last_position, p.quiz_score, p.updated_at
+"#;
+
+/// Отмечает в уроках, пройдены ли они пользователем. Для анонимного запроса ничего не делает.
+pub async fn attach_progress(db_pool: &PgPool, lessons: &mut [Lesson], user: Option<&AuthUser>) -> Result<(), sqlx::Error> {
+    let Some(user) = user else {
+        return Ok(());
+    };
+    if lessons.is_empty() {
+        return Ok(());
+    }
+
+    let ids: Vec<i32> = lessons.iter().map(|l| l.id).collect();
+
+    let completed: HashSet<i32> = sqlx::query_scalar::<_, i32>(
+        "SELECT lesson_id FROM lesson_progress WHERE user_id = $1 AND lesson_id = ANY($2) AND completed_at IS NOT NULL",
+    )
+    .bind(user.user_id)
+    .bind(&ids)
+    .fetch_all(db_pool)
+    .await?
+    .into_iter()
+    .collect();
+
+    for lesson in lessons.iter_mut() {
+        lesson.completed = Some(completed.contains(&lesson.id));
+    }
+
+    Ok(())
+}
+
+/// Сохраняет результат теста по уроку; если урок ещё не начат, заодно начинает его.
+pub async fn record_quiz_score(db_pool: &PgPool, user_id: i32, lesson_id: i32, score: f64) -> Result<(), sqlx::Error> {
+    let query = r#"
+        INSERT INTO lesson_progress (user_id, lesson_id, quiz_score)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (user_id, lesson_id) DO UPDATE SET quiz_score = EXCLUDED.quiz_score, updated_at = NOW()
+    "#;
+
+    sqlx::query(query)
+        .bind(user_id)
+        .bind(lesson_id)
+        .bind(score.clamp(0.0, 1.0))
+        .execute(db_pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Проценты по учебникам, которые пользователь начал, и все начатые им уроки.
+async fn load_progress(db_pool: &PgPool, user_id: i32) -> Result<MyProgress, sqlx::Error> {
+    let query = r#"
+        SELECT t.id AS textbook_id, t.title,
+               COUNT(l.id) AS lessons_total,
+               COUNT(p.completed_at) AS lessons_completed,
+               ROUND(100.0 * COUNT(p.completed_at) / COUNT(l.id), 1)::FLOAT8 AS percent
+        FROM textbook t
+        JOIN lesson l ON l.textbook_id = t.id
+        LEFT JOIN lesson_progress p ON p.lesson_id = l.id AND p.user_id = $1
+        GROUP BY t.id, t.title
+        HAVING COUNT(p.lesson_id) > 0
+        ORDER BY t.id
+    "#;
+
+    let textbooks = sqlx::query_as::<_, TextbookProgress>(query)
+        .bind(user_id)
+        .fetch_all(db_pool)
+        .await?;
+
+    let query = format!(
+        r#"
+        SELECT {LESSON_PROGRESS_COLUMNS}
+        FROM lesson_progress p
+        JOIN lesson l ON l.id = p.lesson_id
+        WHERE p.user_id = $1
+        ORDER BY l.textbook_id, l.position
+        "#
+    );
+
+    let lessons = sqlx::query_as::<_, LessonProgress>(&query)
+        .bind(user_id)
+        .fetch_all(db_pool)
+        .await?;
+
+    Ok(MyProgress { textbooks, lessons })
+}
+
+/// Прогресс текущего пользователя.
+pub async fn get_my_progress(
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> Result<impl IntoResponse, AppError> {
+    let progress = load_progress(&state.db_pool, user.user_id).await?;
+
+    Ok((StatusCode::OK, AnswerJson(progress)))
+}
+
+/// Преподаватель смотрит прогресс ученика.
+pub async fn get_user_progress(
+    State(state): State<AppState>,
+    _teacher: Teacher,
+    Path(user_id): Path<i32>,
+) -> Result<impl IntoResponse, AppError> {
+    let exists = sqlx::query_scalar::<_, bool>("SELECT EXISTS (SELECT 1 FROM users WHERE id = $1)")
+        .bind(user_id)
+        .fetch_one(&state.db_pool)
+        .await?;
+
+    if !exists {
+        return Err(AppError::not_found(format!("User with id {} not found", user_id)));
+    }
+
+    let progress = load_progress(&state.db_pool, user_id).await?;
+
+    Ok((StatusCode::OK, AnswerJson(progress)))
+}
+
+/// Обновляет прогресс по уроку; первый вызов начинает урок.
+pub async fn update_my_progress(
+    State(state): State<AppState>,
+    user: Writer,
+    Path(lesson_id): Path<i32>,
+    ValidatedJson(payload): ValidatedJson<RequestProgress>,
+) -> Result<impl IntoResponse, AppError> {
+    let exists = sqlx::query_scalar::<_, bool>("SELECT EXISTS (SELECT 1 FROM lesson WHERE id = $1)")
+        .bind(lesson_id)
+        .fetch_one(&state.db_pool)
+        .await?;
+
+    if !exists {
+        return Err(AppError::not_found(format!("Lesson with id {} not found", lesson_id)));
+    }
+
+    let query = format!(
+        r#"
+        WITH p AS (
+            INSERT INTO lesson_progress (user_id, lesson_id, time_spent_seconds, last_position, completed_at, quiz_score)
+            VALUES ($1, $2, COALESCE($3, 0), $4, CASE WHEN $5 THEN NOW() END, $6)
+            ON CONFLICT (user_id, lesson_id) DO UPDATE SET
+                time_spent_seconds = lesson_progress.time_spent_seconds + COALESCE($3, 0),
+                last_position = COALESCE($4, lesson_progress.last_position),
+                completed_at = CASE
+                    WHEN $5 IS NULL THEN lesson_progress.completed_at
+                    WHEN $5 THEN COALESCE(lesson_progress.completed_at, NOW())
+                END,
+                quiz_score = COALESCE($6, lesson_progress.quiz_score),
+                updated_at = NOW()
+            RETURNING *
+        )
+        SELECT {LESSON_PROGRESS_COLUMNS}
+        FROM p
+        JOIN lesson l ON l.id = p.lesson_id
+        "#
+    );
+
+    let progress = sqlx::query_as::<_, LessonProgress>(&query)
+        .bind(user.user_id)
+        .bind(lesson_id)
+        .bind(payload.add_seconds)
+        .bind(payload.last_position)
+        .bind(payload.completed)
+        .bind(payload.quiz_score)
+        .fetch_one(&state.db_pool)
+        .await?;
+
+    Ok((StatusCode::OK, AnswerJson(progress)))
+}
+
+/// Сбрасывает прогресс по уроку.
+pub async fn delete_my_progress(
+    State(state): State<AppState>,
+    user: Writer,
+    Path(lesson_id): Path<i32>,
+) -> Result<impl IntoResponse, AppError> {
+    sqlx::query("DELETE FROM lesson_progress WHERE user_id = $1 AND lesson_id = $2 RETURNING lesson_id")
+        .bind(user.user_id)
+        .bind(lesson_id)
+        .fetch_optional(&state.db_pool)
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("No progress for lesson {}", lesson_id)))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
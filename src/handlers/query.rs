@@ -1,6 +1,66 @@
 use serde::Deserialize;
 
+use crate::arabic::transliterate::Scheme;
+use crate::lessons::serializers::{Gender, PartOfSpeech, PluralType};
+use crate::utils::pagination::HasPagination;
+
 #[derive(Deserialize)]
 pub struct WordQuery {
     pub lesson_id: Option<i32>,
+    pub part_of_speech: Option<PartOfSpeech>,
+    pub gender: Option<Gender>,
+    pub plural_type: Option<PluralType>,
+}
+
+#[derive(Deserialize)]
+pub struct WordSearchQuery {
+    pub q: String,
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+impl HasPagination for WordSearchQuery {
+    fn page(&self) -> Option<i64> {
+        self.page
+    }
+
+    fn limit(&self) -> Option<i64> {
+        self.limit
+    }
+}
+
+#[derive(Deserialize)]
+pub struct AnalyzeQuery {
+    pub term: String,
+}
+
+/// `?transliteration=buckwalter|din|ala-lc` добавляет в ответ поле `transliteration`.
+#[derive(Deserialize)]
+pub struct TransliterationQuery {
+    pub transliteration: Option<Scheme>,
+}
+
+#[derive(Deserialize)]
+pub struct TransliterateQuery {
+    pub text: String,
+    pub scheme: Scheme,
+}
+
+#[derive(Deserialize)]
+pub struct ReverseTransliterateQuery {
+    pub text: String,
+}
+
+/// Какие карточки выдать: `limit` штук (по умолчанию 20), при желании только из учебника или урока.
+#[derive(Deserialize)]
+pub struct ReviewQuery {
+    pub limit: Option<i64>,
+    pub textbook_id: Option<i32>,
+    pub lesson_id: Option<i32>,
+}
+
+/// Без `seed` тест генерируется случайно, а использованный `seed` возвращается в ответе.
+#[derive(Deserialize)]
+pub struct QuizQuery {
+    pub seed: Option<u64>,
 }
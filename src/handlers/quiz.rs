@@ -0,0 +1,90 @@
+use axum::response::{IntoResponse, Json as AnswerJson};
+use axum::{extract::State, http::StatusCode};
+use sqlx::PgPool;
+
+use crate::auth::extractor::AuthUser;
+use crate::auth::permissions::Permission;
+use crate::handlers::progress::record_quiz_score;
+use crate::handlers::query::QuizQuery;
+use crate::handlers::translation::attach_definitions;
+use crate::lessons::serializers::{LessonQuiz, RequestQuizGrade, Word};
+use crate::lessons::state::AppState;
+use crate::quiz::{generate, grade, Answer, Quiz, QuizWord};
+use crate::utils::error::AppError;
+use crate::utils::extract::{Json, Path, Query};
+use crate::utils::lang::Lang;
+
+/// Собирает тест по словам урока; неправильные варианты берутся из слов всего учебника.
+async fn build_quiz(db_pool: &PgPool, lesson_id: i32, seed: u64, lang: &Lang) -> Result<Quiz, AppError> {
+    let text = sqlx::query_scalar::<_, String>("SELECT text FROM lesson WHERE id = $1")
+        .bind(lesson_id)
+        .fetch_optional(db_pool)
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Lesson with id {} not found", lesson_id)))?;
+
+    // Порядок фиксирован: от него зависит, какой тест получится из того же seed
+    let query = r#"
+        SELECT w.* FROM word w
+        JOIN lesson l ON l.id = w.lesson_id
+        JOIN lesson cur ON cur.id = $1
+        WHERE l.textbook_id = cur.textbook_id
+        ORDER BY w.id
+    "#;
+
+    let mut words = sqlx::query_as::<_, Word>(query)
+        .bind(lesson_id)
+        .fetch_all(db_pool)
+        .await?;
+
+    attach_definitions(db_pool, &mut words, lang).await?;
+
+    let (lesson_words, other_words): (Vec<Word>, Vec<Word>) = words.into_iter().partition(|w| w.lesson_id == lesson_id);
+
+    if lesson_words.is_empty() {
+        return Err(AppError::not_found(format!("No words found for lesson {}", lesson_id)));
+    }
+
+    let lesson_words: Vec<QuizWord> = lesson_words.into_iter().map(QuizWord::from).collect();
+    let pool: Vec<QuizWord> = lesson_words
+        .iter()
+        .cloned()
+        .chain(other_words.into_iter().map(QuizWord::from))
+        .collect();
+
+    Ok(generate(&lesson_words, &pool, &text, seed))
+}
+
+pub async fn get_lesson_quiz(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    Query(params): Query<QuizQuery>,
+    lang: Lang,
+) -> Result<impl IntoResponse, AppError> {
+    // Случайный seed не больше u32, чтобы без потерь пережить числа с плавающей точкой в JavaScript
+    let seed = params.seed.unwrap_or_else(|| u64::from(rand::random::<u32>()));
+
+    let quiz = build_quiz(&state.db_pool, id, seed, &lang).await?;
+
+    Ok((StatusCode::OK, AnswerJson(LessonQuiz { lesson_id: id, seed, items: quiz.items })))
+}
+
+/// Проверяет ответы на тест. У авторизованного пользователя результат сохраняется в прогресс по уроку,
+/// если только он не пришёл с API-ключом на чтение.
+pub async fn grade_lesson_quiz(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    lang: Lang,
+    user: Option<AuthUser>,
+    Json(payload): Json<RequestQuizGrade>,
+) -> Result<impl IntoResponse, AppError> {
+    let quiz = build_quiz(&state.db_pool, id, payload.seed, &lang).await?;
+
+    let responses: Vec<(usize, Answer)> = payload.answers.into_iter().map(|a| (a.item, a.answer)).collect();
+    let result = grade(&quiz, &responses, payload.strictness);
+
+    if let Some(user) = user.filter(|user| user.require(Permission::RecordProgress).is_ok()) {
+        record_quiz_score(&state.db_pool, user.user_id, id, result.score).await?;
+    }
+
+    Ok((StatusCode::OK, AnswerJson(result)))
+}
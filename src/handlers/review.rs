@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+
+use axum::response::{IntoResponse, Json as AnswerJson};
+use axum::{extract::State, http::StatusCode};
+use chrono::Utc;
+use sqlx::{Postgres, QueryBuilder};
+
+use crate::auth::extractor::AuthUser;
+use crate::auth::permissions::Writer;
+use crate::handlers::query::ReviewQuery;
+use crate::handlers::translation::attach_definitions;
+use crate::lessons::serializers::{RequestReview, ReviewCard, Word, WordReview};
+use crate::lessons::state::AppState;
+use crate::srs::ReviewState;
+use crate::utils::error::AppError;
+use crate::utils::extract::{Json, Path, Query};
+use crate::utils::lang::Lang;
+
+const DEFAULT_CARDS: i64 = 20;
+const MAX_CARDS: i64 = 100;
+
+fn push_scope(builder: &mut QueryBuilder<'_, Postgres>, params: &ReviewQuery) {
+    if let Some(textbook_id) = params.textbook_id {
+        builder.push(" AND l.textbook_id = ").push_bind(textbook_id);
+    }
+    if let Some(lesson_id) = params.lesson_id {
+        builder.push(" AND w.lesson_id = ").push_bind(lesson_id);
+    }
+}
+
+/// Карточки к повторению: сначала просроченные (самые давние первыми),
+/// а если их меньше `limit`, добавляются новые слова в порядке уроков учебника.
+pub async fn get_due_reviews(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Query(params): Query<ReviewQuery>,
+    lang: Lang,
+) -> Result<impl IntoResponse, AppError> {
+    let limit = params.limit.unwrap_or(DEFAULT_CARDS).clamp(1, MAX_CARDS);
+
+    let mut builder = QueryBuilder::new(
+        r#"
+        SELECT r.* FROM word_review r
+        JOIN word w ON w.id = r.word_id
+        JOIN lesson l ON l.id = w.lesson_id
+        WHERE r.user_id = "#,
+    );
+    builder.push_bind(user.user_id);
+    builder.push(" AND r.due_at <= NOW()");
+    push_scope(&mut builder, &params);
+    builder.push(" ORDER BY r.due_at LIMIT ").push_bind(limit);
+
+    let reviews = builder.build_query_as::<WordReview>().fetch_all(&state.db_pool).await?;
+
+    let ids: Vec<i32> = reviews.iter().map(|r| r.word_id).collect();
+    let mut due_words: HashMap<i32, Word> = sqlx::query_as::<_, Word>("SELECT * FROM word WHERE id = ANY($1)")
+        .bind(&ids)
+        .fetch_all(&state.db_pool)
+        .await?
+        .into_iter()
+        .map(|word| (word.id, word))
+        .collect();
+
+    // Слова и их состояния идут параллельно: переводы подставляются сразу во все слова
+    let mut words: Vec<Word> = Vec::new();
+    let mut states: Vec<Option<WordReview>> = Vec::new();
+    for review in reviews {
+        if let Some(word) = due_words.remove(&review.word_id) {
+            words.push(word);
+            states.push(Some(review));
+        }
+    }
+
+    let remaining = limit - words.len() as i64;
+    if remaining > 0 {
+        let mut builder = QueryBuilder::new(
+            r#"
+            SELECT w.* FROM word w
+            JOIN lesson l ON l.id = w.lesson_id
+            WHERE NOT EXISTS (SELECT 1 FROM word_review r WHERE r.word_id = w.id AND r.user_id = "#,
+        );
+        builder.push_bind(user.user_id);
+        builder.push(")");
+        push_scope(&mut builder, &params);
+        builder.push(" ORDER BY l.textbook_id, l.position, w.id LIMIT ").push_bind(remaining);
+
+        for word in builder.build_query_as::<Word>().fetch_all(&state.db_pool).await? {
+            words.push(word);
+            states.push(None);
+        }
+    }
+
+    attach_definitions(&state.db_pool, &mut words, &lang).await?;
+
+    let cards: Vec<ReviewCard> = words
+        .into_iter()
+        .zip(states)
+        .map(|(word, review)| ReviewCard { word, review })
+        .collect();
+
+    Ok((StatusCode::OK, AnswerJson(cards)))
+}
+
+/// Оценка ответа по карточке: пересчитывает интервал и дату следующего повторения.
+pub async fn review_word(
+    State(state): State<AppState>,
+    user: Writer,
+    Path(word_id): Path<i32>,
+    Json(payload): Json<RequestReview>,
+) -> Result<impl IntoResponse, AppError> {
+    let mut tx = state.db_pool.begin().await?;
+
+    let exists = sqlx::query_scalar::<_, bool>("SELECT EXISTS (SELECT 1 FROM word WHERE id = $1)")
+        .bind(word_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+    if !exists {
+        return Err(AppError::not_found(format!("Word with id {} not found", word_id)));
+    }
+
+    let current = sqlx::query_as::<_, WordReview>(
+        "SELECT * FROM word_review WHERE user_id = $1 AND word_id = $2 FOR UPDATE",
+    )
+    .bind(user.user_id)
+    .bind(word_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let now = Utc::now();
+    let next = current
+        .as_ref()
+        .map(ReviewState::from)
+        .unwrap_or_else(|| ReviewState::new(now))
+        .schedule(payload.grade, now);
+
+    let query = r#"
+        INSERT INTO word_review (user_id, word_id, ease, interval_days, repetitions, lapses, due_at, last_reviewed_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        ON CONFLICT (user_id, word_id) DO UPDATE SET
+            ease = EXCLUDED.ease,
+            interval_days = EXCLUDED.interval_days,
+            repetitions = EXCLUDED.repetitions,
+            lapses = EXCLUDED.lapses,
+            due_at = EXCLUDED.due_at,
+            last_reviewed_at = EXCLUDED.last_reviewed_at
+        RETURNING word_id, ease, interval_days, repetitions, lapses, due_at, last_reviewed_at
+    "#;
+
+    let review = sqlx::query_as::<_, WordReview>(query)
+        .bind(user.user_id)
+        .bind(word_id)
+        .bind(next.ease)
+        .bind(next.interval_days)
+        .bind(next.repetitions)
+        .bind(next.lapses)
+        .bind(next.due_at)
+        .bind(now)
+        .fetch_one(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok((StatusCode::OK, AnswerJson(review)))
+}
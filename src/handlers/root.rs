@@ -0,0 +1,72 @@
+use axum::response::{IntoResponse, Json as AnswerJson};
+use axum::{extract::State, http::StatusCode};
+
+use crate::arabic::root::canonical_root;
+use crate::handlers::query::TransliterationQuery;
+use crate::handlers::translation::attach_definitions;
+use crate::lessons::serializers::{RootCount, Transliterated, Word};
+use crate::lessons::state::AppState;
+use crate::utils::error::AppError;
+use crate::utils::extract::{Path, Query};
+use crate::utils::lang::Lang;
+
+/// Все слова одного корня по всем урокам. Корень можно передать как "ك-ت-ب" или "كتب".
+pub async fn get_words_by_root(
+    State(state): State<AppState>,
+    Path(root): Path<String>,
+    Query(translit): Query<TransliterationQuery>,
+    lang: Lang,
+) -> Result<impl IntoResponse, AppError> {
+    let root = canonical_root(&root)
+        .ok_or_else(|| AppError::bad_request("Root must consist of 3 or 4 Arabic letters, e.g. ك-ت-ب"))?;
+
+    let query = r#"
+        SELECT * FROM word
+        WHERE root = $1
+        ORDER BY lesson_id, id
+    "#;
+
+    let mut words = sqlx::query_as::<_, Word>(query)
+        .bind(&root)
+        .fetch_all(&state.db_pool)
+        .await?;
+
+    if words.is_empty() {
+        return Err(AppError::not_found(format!("No words found for root {}", root)));
+    }
+
+    attach_definitions(&state.db_pool, &mut words, &lang).await?;
+
+    Ok((StatusCode::OK, AnswerJson(Transliterated::all(words, translit.transliteration))))
+}
+
+/// Сколько слов каждого корня встречается в уроках учебника — для изучения словесных семей.
+pub async fn get_textbook_roots(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<impl IntoResponse, AppError> {
+    let exists = sqlx::query_scalar::<_, bool>("SELECT EXISTS (SELECT 1 FROM textbook WHERE id = $1)")
+        .bind(id)
+        .fetch_one(&state.db_pool)
+        .await?;
+
+    if !exists {
+        return Err(AppError::not_found(format!("Textbook with id {} not found", id)));
+    }
+
+    let query = r#"
+        SELECT w.root AS root, COUNT(*) AS word_count
+        FROM word w
+        JOIN lesson l ON l.id = w.lesson_id
+        WHERE l.textbook_id = $1 AND w.root IS NOT NULL
+        GROUP BY w.root
+        ORDER BY word_count DESC, w.root
+    "#;
+
+    let roots = sqlx::query_as::<_, RootCount>(query)
+        .bind(id)
+        .fetch_all(&state.db_pool)
+        .await?;
+
+    Ok((StatusCode::OK, AnswerJson(roots)))
+}
@@ -1,15 +1,22 @@
+use std::collections::HashMap;
+
 use axum::debug_handler;
 use axum::response::{IntoResponse, Json as AnswerJson};
-use axum::{
-    extract::{Json, Path, Query, State},
-    http::StatusCode,
-};
+use axum::{extract::State, http::StatusCode};
 
 use serde::Deserialize;
-use sqlx::QueryBuilder;
-
-use crate::lessons::serializers::{RequestTextbook, Textbook};
+use sqlx::{PgPool, QueryBuilder};
+
+use crate::lessons::serializers::{Lesson, LessonTree, RequestTextbook, Textbook, TextbookTree, Transliterated, Word};
+use crate::auth::extractor::AuthUser;
+use crate::auth::permissions::Editor;
+use crate::handlers::progress::attach_progress;
+use crate::handlers::query::TransliterationQuery;
+use crate::handlers::translation::attach_definitions;
 use crate::lessons::state::AppState;
+use crate::utils::error::AppError;
+use crate::utils::extract::{Path, Query, ValidatedJson};
+use crate::utils::lang::Lang;
 use crate::utils::pagination::{HasPagination, PaginateQuery, PaginateResult};
 
 impl PaginateQuery for Textbook {}
@@ -34,13 +41,13 @@ impl HasPagination for TextbookQuery {
 pub async fn get_all_textbooks(
     State(state): State<AppState>,
     Query(params): Query<TextbookQuery>,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, AppError> {
     let mut builder = QueryBuilder::new("SELECT * FROM textbook WHERE 1=1");
 
     builder.push(" ORDER BY id");
 
-    match Textbook::paginate_query(&state.db_pool, builder, &params).await {
-        Ok(PaginateResult::Success(records)) => {
+    match Textbook::paginate_query(&state.db_pool, builder, &params).await? {
+        PaginateResult::Success(records) => {
             let mut response = AnswerJson(records).into_response();
 
             let total_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM textbook")
@@ -50,66 +57,147 @@ pub async fn get_all_textbooks(
 
             response = Textbook::add_pagination_headers(response, total_count, &params);
 
-            response
-        }
-        Ok(PaginateResult::NotFound) => StatusCode::NOT_FOUND.into_response(),
-        Err(err) => {
-            eprintln!("Failed to get textbooks: {:?}", err);
-            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+            Ok(response)
         }
+        PaginateResult::NotFound => Err(AppError::not_found("Page not found")),
     }
 }
 
 
 
-pub async fn get_textbook(State(state): State<AppState>, Path(id): Path<i32>) -> impl IntoResponse {
+pub async fn get_textbook(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<impl IntoResponse, AppError> {
     let query = r#"
         SELECT * FROM textbook
         WHERE id = $1
     "#;
 
-    let result = sqlx::query_as::<_, Textbook>(query)
+    let textbook = sqlx::query_as::<_, Textbook>(query)
         .bind(id)
         .fetch_optional(&state.db_pool)
-        .await;
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Textbook with id {} not found", id)))?;
+
+    Ok((StatusCode::OK, AnswerJson(textbook)))
+}
+
+async fn fetch_textbook(db_pool: &PgPool, id: i32) -> Result<Textbook, AppError> {
+    sqlx::query_as::<_, Textbook>("SELECT * FROM textbook WHERE id = $1")
+        .bind(id)
+        .fetch_optional(db_pool)
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Textbook with id {} not found", id)))
+}
+
+/// Уроки учебника в порядке `position`.
+pub async fn get_textbook_lessons(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    Query(params): Query<TextbookQuery>,
+    Query(translit): Query<TransliterationQuery>,
+    user: Option<AuthUser>,
+) -> Result<impl IntoResponse, AppError> {
+    fetch_textbook(&state.db_pool, id).await?;
+
+    let mut builder = QueryBuilder::new("SELECT * FROM lesson WHERE textbook_id = ");
+    builder.push_bind(id);
+    builder.push(" ORDER BY position");
+
+    match Lesson::paginate_query(&state.db_pool, builder, &params).await? {
+        PaginateResult::Success(mut records) => {
+            attach_progress(&state.db_pool, &mut records, user.as_ref()).await?;
+
+            let mut response = AnswerJson(Transliterated::all(records, translit.transliteration)).into_response();
+
+            let total_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM lesson WHERE textbook_id = $1")
+                .bind(id)
+                .fetch_one(&state.db_pool)
+                .await
+                .unwrap_or(0);
+
+            response = Lesson::add_pagination_headers(response, total_count, &params);
+
+            Ok(response)
+        }
+        PaginateResult::NotFound => Err(AppError::not_found("Page not found")),
+    }
+}
+
+/// Учебник со всеми уроками и словами одним ответом (для офлайн-режима мобильного приложения).
+/// Слова всех уроков и их переводы выбираются одним запросом каждые, а не по запросу на урок.
+pub async fn get_textbook_tree(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    lang: Lang,
+    user: Option<AuthUser>,
+) -> Result<impl IntoResponse, AppError> {
+    let textbook = fetch_textbook(&state.db_pool, id).await?;
+
+    let mut lessons = sqlx::query_as::<_, Lesson>("SELECT * FROM lesson WHERE textbook_id = $1 ORDER BY position")
+        .bind(id)
+        .fetch_all(&state.db_pool)
+        .await?;
+
+    attach_progress(&state.db_pool, &mut lessons, user.as_ref()).await?;
+
+    let query = r#"
+        SELECT w.* FROM word w
+        JOIN lesson l ON l.id = w.lesson_id
+        WHERE l.textbook_id = $1
+        ORDER BY w.id
+    "#;
+
+    let mut words = sqlx::query_as::<_, Word>(query)
+        .bind(id)
+        .fetch_all(&state.db_pool)
+        .await?;
+
+    attach_definitions(&state.db_pool, &mut words, &lang).await?;
 
-    match result {
-        Ok(Some(textbook)) => (StatusCode::OK, AnswerJson(textbook)).into_response(),
-        Ok(None) => StatusCode::NOT_FOUND.into_response(),
-        Err(_) => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    let mut by_lesson: HashMap<i32, Vec<Word>> = HashMap::new();
+    for word in words {
+        by_lesson.entry(word.lesson_id).or_default().push(word);
     }
+
+    let lessons = lessons
+        .into_iter()
+        .map(|lesson| {
+            let words = by_lesson.remove(&lesson.id).unwrap_or_default();
+            LessonTree { lesson, words }
+        })
+        .collect();
+
+    Ok((StatusCode::OK, AnswerJson(TextbookTree { textbook, lessons })))
 }
 
 pub async fn create_textbook(
     State(state): State<AppState>,
-    Json(payload): Json<RequestTextbook>,
-) -> impl IntoResponse {
+    _user: Editor,
+    ValidatedJson(payload): ValidatedJson<RequestTextbook>,
+) -> Result<impl IntoResponse, AppError> {
     let query = r#"
         INSERT INTO textbook (title, description)
         VALUES ($1, $2)
         RETURNING id, title, description
     "#;
 
-    let result = sqlx::query_as::<_, Textbook>(query)
+    let textbook = sqlx::query_as::<_, Textbook>(query)
         .bind(&payload.title)
         .bind(&payload.description)
         .fetch_one(&state.db_pool)
-        .await;
+        .await?;
 
-    match result {
-        Ok(textbook) => (StatusCode::CREATED, AnswerJson(textbook)).into_response(),
-        Err(err) => {
-            eprint!("Failed to create textbook: {:?}", err);
-            StatusCode::INTERNAL_SERVER_ERROR.into_response()
-        }
-    }
+    Ok((StatusCode::CREATED, AnswerJson(textbook)))
 }
 
 pub async fn update_textbook(
     State(state): State<AppState>,
+    _user: Editor,
     Path(id): Path<i32>,
-    Json(payload): Json<RequestTextbook>,
-) -> impl IntoResponse {
+    ValidatedJson(payload): ValidatedJson<RequestTextbook>,
+) -> Result<impl IntoResponse, AppError> {
     let query = r#"
         UPDATE textbook
         SET title = $1, description = $2
@@ -117,46 +205,34 @@ pub async fn update_textbook(
         RETURNING id, title, description
     "#;
 
-    let result = sqlx::query_as::<_, Textbook>(query)
+    let textbook = sqlx::query_as::<_, Textbook>(query)
         .bind(&payload.title)
         .bind(&payload.description)
         .bind(id)
         .fetch_optional(&state.db_pool)
-        .await;
-
-    match result {
-        Ok(result) => match result {
-            Some(result) => AnswerJson(result).into_response(),
-            None => StatusCode::NOT_FOUND.into_response(),
-        },
-        Err(err) => {
-            eprint!("Failed to update textbook: {:?}", err);
-            StatusCode::INTERNAL_SERVER_ERROR.into_response()
-        }
-    }
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Textbook with id {} not found", id)))?;
+
+    Ok(AnswerJson(textbook))
 }
 
 pub async fn delete_textbook(
     State(state): State<AppState>,
+    _user: Editor,
     Path(id): Path<i32>,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, AppError> {
     let query = r#"
         DELETE FROM textbook
         WHERE id = $1
         RETURNING id
     "#;
 
-    let result = sqlx::query(query)
+    sqlx::query(query)
         .bind(id)
         .fetch_optional(&state.db_pool)
-        .await;
-
-    match result {
-        Ok(Some(_)) => StatusCode::NO_CONTENT.into_response(),
-        Ok(None) => StatusCode::NOT_FOUND.into_response(),
-        Err(err) => {
-            eprint!("Failed to delete textbook {:?}", err);
-            StatusCode::INTERNAL_SERVER_ERROR.into_response()
-        }
-    }
+        .await
+        .map_err(|e| AppError::from_delete(e, "Textbook"))?
+        .ok_or_else(|| AppError::not_found(format!("Textbook with id {} not found", id)))?;
+
+    Ok(StatusCode::NO_CONTENT)
 }
@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+
+use axum::response::{IntoResponse, Json as AnswerJson};
+use axum::{extract::State, http::StatusCode};
+use sqlx::{PgConnection, PgPool};
+
+use crate::auth::permissions::Editor;
+use crate::lessons::serializers::{RequestDefinition, RequestTranslation, Word, WordTranslation};
+use crate::lessons::state::AppState;
+use crate::utils::error::AppError;
+use crate::utils::extract::{Path, ValidatedJson};
+use crate::utils::lang::{default_language, is_language_code, Lang};
+
+/// Переводы из тела запроса: `definition` идёт на языке по умолчанию, затем `translations`.
+/// Если язык повторяется, побеждает последний перевод.
+pub fn collect_translations(definition: &Option<String>, translations: &[RequestTranslation]) -> Vec<(String, String)> {
+    let mut result = Vec::new();
+
+    if let Some(definition) = definition {
+        result.push((default_language(), definition.clone()));
+    }
+    for translation in translations {
+        result.push((translation.lang.clone(), translation.definition.clone()));
+    }
+
+    result
+}
+
+/// Добавляет или заменяет переводы слова.
+pub async fn save_translations(
+    conn: &mut PgConnection,
+    word_id: i32,
+    translations: &[(String, String)],
+) -> Result<(), sqlx::Error> {
+    let query = r#"
+        INSERT INTO word_translation (word_id, lang, definition)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (word_id, lang) DO UPDATE SET definition = EXCLUDED.definition
+    "#;
+
+    for (lang, definition) in translations {
+        sqlx::query(query)
+            .bind(word_id)
+            .bind(lang)
+            .bind(definition)
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Подставляет в слова перевод на первом из предпочитаемых языков, для которого он есть.
+/// Если ни одного такого нет, берётся самый первый добавленный перевод.
+pub async fn attach_definitions(db_pool: &PgPool, words: &mut [Word], lang: &Lang) -> Result<(), sqlx::Error> {
+    if words.is_empty() {
+        return Ok(());
+    }
+
+    let ids: Vec<i32> = words.iter().map(|w| w.id).collect();
+
+    let rows = sqlx::query_as::<_, (i32, String, String)>(
+        "SELECT word_id, lang, definition FROM word_translation WHERE word_id = ANY($1) ORDER BY id",
+    )
+    .bind(&ids)
+    .fetch_all(db_pool)
+    .await?;
+
+    let mut by_word: HashMap<i32, Vec<(String, String)>> = HashMap::new();
+    for (word_id, lang, definition) in rows {
+        by_word.entry(word_id).or_default().push((lang, definition));
+    }
+
+    for word in words.iter_mut() {
+        let translations = by_word.remove(&word.id).unwrap_or_default();
+
+        let best = translations
+            .into_iter()
+            .min_by_key(|(lang_code, _)| lang.0.iter().position(|l| l == lang_code).unwrap_or(usize::MAX));
+
+        if let Some((lang_code, definition)) = best {
+            word.definition = definition;
+            word.language = Some(lang_code);
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn get_word_translations(
+    State(state): State<AppState>,
+    Path(word_id): Path<i32>,
+) -> Result<impl IntoResponse, AppError> {
+    let translations = sqlx::query_as::<_, WordTranslation>(
+        "SELECT lang, definition FROM word_translation WHERE word_id = $1 ORDER BY id",
+    )
+    .bind(word_id)
+    .fetch_all(&state.db_pool)
+    .await?;
+
+    if translations.is_empty() {
+        return Err(AppError::not_found(format!("Word with id {} not found", word_id)));
+    }
+
+    Ok((StatusCode::OK, AnswerJson(translations)))
+}
+
+fn check_language(lang: &str) -> Result<(), AppError> {
+    if !is_language_code(lang) {
+        return Err(AppError::bad_request("Language must be a lowercase ISO 639 code, e.g. ru or en"));
+    }
+    Ok(())
+}
+
+pub async fn put_word_translation(
+    State(state): State<AppState>,
+    _user: Editor,
+    Path((word_id, lang)): Path<(i32, String)>,
+    ValidatedJson(payload): ValidatedJson<RequestDefinition>,
+) -> Result<impl IntoResponse, AppError> {
+    check_language(&lang)?;
+
+    // Несуществующее слово даст нарушение внешнего ключа, а нам нужен 404
+    let exists = sqlx::query_scalar::<_, bool>("SELECT EXISTS (SELECT 1 FROM word WHERE id = $1)")
+        .bind(word_id)
+        .fetch_one(&state.db_pool)
+        .await?;
+
+    if !exists {
+        return Err(AppError::not_found(format!("Word with id {} not found", word_id)));
+    }
+
+    let query = r#"
+        INSERT INTO word_translation (word_id, lang, definition)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (word_id, lang) DO UPDATE SET definition = EXCLUDED.definition
+        RETURNING lang, definition
+    "#;
+
+    let translation = sqlx::query_as::<_, WordTranslation>(query)
+        .bind(word_id)
+        .bind(&lang)
+        .bind(&payload.definition)
+        .fetch_one(&state.db_pool)
+        .await?;
+
+    Ok((StatusCode::OK, AnswerJson(translation)))
+}
+
+/// Последний перевод удалить нельзя: у слова всегда должно быть определение.
+pub async fn delete_word_translation(
+    State(state): State<AppState>,
+    _user: Editor,
+    Path((word_id, lang)): Path<(i32, String)>,
+) -> Result<impl IntoResponse, AppError> {
+    let mut tx = state.db_pool.begin().await?;
+
+    let langs = sqlx::query_scalar::<_, String>(
+        "SELECT lang FROM word_translation WHERE word_id = $1 FOR UPDATE",
+    )
+    .bind(word_id)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    if !langs.contains(&lang) {
+        return Err(AppError::not_found(format!("Translation {} for word {} not found", lang, word_id)));
+    }
+
+    if langs.len() == 1 {
+        return Err(AppError::conflict("Cannot delete the only translation of a word"));
+    }
+
+    sqlx::query("DELETE FROM word_translation WHERE word_id = $1 AND lang = $2")
+        .bind(word_id)
+        .bind(&lang)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,34 @@
+use axum::response::{IntoResponse, Json as AnswerJson};
+use axum::http::StatusCode;
+
+use crate::arabic::transliterate::{from_buckwalter, transliterate};
+use crate::handlers::query::{ReverseTransliterateQuery, TransliterateQuery};
+use crate::lessons::serializers::TransliterateResult;
+use crate::utils::error::AppError;
+use crate::utils::extract::Query;
+
+/// Арабский текст латиницей по выбранной схеме.
+pub async fn transliterate_text(
+    Query(params): Query<TransliterateQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    if params.text.trim().is_empty() {
+        return Err(AppError::bad_request("Query parameter text must not be empty"));
+    }
+
+    let result = transliterate(&params.text, params.scheme);
+
+    Ok((StatusCode::OK, AnswerJson(TransliterateResult { text: params.text, result })))
+}
+
+/// Буквы Бакуолтера обратно в арабское письмо — для набора на латинской клавиатуре.
+pub async fn buckwalter_to_arabic(
+    Query(params): Query<ReverseTransliterateQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    if params.text.trim().is_empty() {
+        return Err(AppError::bad_request("Query parameter text must not be empty"));
+    }
+
+    let result = from_buckwalter(&params.text);
+
+    Ok((StatusCode::OK, AnswerJson(TransliterateResult { text: params.text, result })))
+}
@@ -1,149 +1,229 @@
 use axum::response::{IntoResponse, Json as AnswerJson};
-use axum::{
-    extract::{Json, Path, Query, State},
-    http::StatusCode,
-};
-use serde::Deserialize;
-
-use crate::handlers::query::WordQuery;
-use crate::lessons::serializers::{RequestWord, Word};
+use axum::{extract::State, http::StatusCode};
+
+use sqlx::QueryBuilder;
+
+use crate::arabic::normalize::normalize;
+use crate::arabic::root::canonical_root;
+use crate::arabic::stemmer::analyze;
+use crate::handlers::audio::remove_stored_files;
+use crate::handlers::query::{TransliterationQuery, WordQuery, WordSearchQuery};
+use crate::handlers::translation::{attach_definitions, collect_translations, save_translations};
+use crate::lessons::serializers::{CreatedWord, RequestWord, Transliterated, Word};
+use crate::auth::permissions::Editor;
 use crate::lessons::state::AppState;
+use crate::utils::error::AppError;
+use crate::utils::extract::{Path, Query, ValidatedJson};
+use crate::utils::lang::Lang;
+use crate::utils::pagination::{PaginateQuery, PaginateResult};
+
+impl PaginateQuery for Word {}
 
 pub async fn get_words(
     State(state): State<AppState>,
     Query(params): Query<WordQuery>,
-) -> impl IntoResponse {
-    let query;
-    let words_result;
+    Query(translit): Query<TransliterationQuery>,
+    lang: Lang,
+) -> Result<impl IntoResponse, AppError> {
+    let mut builder = QueryBuilder::new("SELECT * FROM word WHERE 1=1");
 
     if let Some(lesson_id) = params.lesson_id {
-        query = "SELECT * FROM word WHERE lesson_id = $1";
-        words_result = sqlx::query_as::<_, Word>(query)
-            .bind(lesson_id)
-            .fetch_all(&state.db_pool)
-            .await;
-    } else {
-        query = "SElECT * FROM word";
-        words_result = sqlx::query_as::<_, Word>(query)
-            .fetch_all(&state.db_pool)
-            .await;
+        builder.push(" AND lesson_id = ").push_bind(lesson_id);
+    }
+    if let Some(part_of_speech) = params.part_of_speech {
+        builder.push(" AND part_of_speech = ").push_bind(part_of_speech);
+    }
+    if let Some(gender) = params.gender {
+        builder.push(" AND gender = ").push_bind(gender);
+    }
+    if let Some(plural_type) = params.plural_type {
+        builder.push(" AND plural_type = ").push_bind(plural_type);
     }
 
-    match words_result {
-        Ok(words) => {
-            if words.is_empty() {
-                StatusCode::NOT_FOUND.into_response()
-            } else {
-                (StatusCode::OK, Json(words)).into_response()
-            }
-        }
-        Err(err) => {
-            eprintln!("DB error: {:?}", err);
-            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+    builder.push(" ORDER BY id");
+
+    let mut words = builder
+        .build_query_as::<Word>()
+        .fetch_all(&state.db_pool)
+        .await?;
+
+    if words.is_empty() {
+        return Err(AppError::not_found("No words found"));
+    }
+
+    attach_definitions(&state.db_pool, &mut words, &lang).await?;
+
+    Ok((StatusCode::OK, AnswerJson(Transliterated::all(words, translit.transliteration))))
+}
+
+/// Поиск слова без учёта огласовок, татвиля и вариантов алифа/та марбуты:
+/// "كتب" находит и "كَتَبَ", и "كُتُب". Сначала точные совпадения, затем по началу слова.
+pub async fn search_words(
+    State(state): State<AppState>,
+    Query(params): Query<WordSearchQuery>,
+    Query(translit): Query<TransliterationQuery>,
+    lang: Lang,
+) -> Result<impl IntoResponse, AppError> {
+    let needle = normalize(params.q.trim());
+    if needle.is_empty() {
+        return Err(AppError::bad_request("Query parameter q must not be empty"));
+    }
+
+    let escaped = needle.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+
+    let mut builder = QueryBuilder::new("SELECT * FROM word WHERE term_normalized LIKE ");
+    builder.push_bind(format!("%{}%", escaped));
+    builder.push(" ORDER BY term_normalized = ").push_bind(needle.clone());
+    builder.push(" DESC, term_normalized LIKE ").push_bind(format!("{}%", escaped));
+    builder.push(" DESC, length(term_normalized), id");
+
+    match Word::paginate_query(&state.db_pool, builder, &params).await? {
+        PaginateResult::Success(mut words) => {
+            attach_definitions(&state.db_pool, &mut words, &lang).await?;
+            Ok((StatusCode::OK, AnswerJson(Transliterated::all(words, translit.transliteration))))
         }
+        PaginateResult::NotFound => Err(AppError::not_found("Page not found")),
     }
 }
 
-pub async fn get_word(State(state): State<AppState>, Path(id): Path<i32>) -> impl IntoResponse {
+pub async fn get_word(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    Query(translit): Query<TransliterationQuery>,
+    lang: Lang,
+) -> Result<impl IntoResponse, AppError> {
     let query = "SELECT * FROM word WHERE id = $1";
 
-    let result = sqlx::query_as::<_, Word>(query)
+    let mut word = sqlx::query_as::<_, Word>(query)
         .bind(id)
         .fetch_optional(&state.db_pool)
-        .await;
-
-    match result {
-        Ok(result) => match result {
-            Some(result) => AnswerJson(result).into_response(),
-            None => StatusCode::NOT_FOUND.into_response(),
-        },
-        Err(err) => {
-            eprint!("Failed to get word: {:?}", err);
-            StatusCode::INTERNAL_SERVER_ERROR.into_response()
-        }
-    }
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Word with id {} not found", id)))?;
+
+    attach_definitions(&state.db_pool, std::slice::from_mut(&mut word), &lang).await?;
+
+    Ok(AnswerJson(Transliterated::new(word, translit.transliteration)))
 }
 
 pub async fn create_word(
     State(state): State<AppState>,
-    Json(payload): Json<RequestWord>,
-) -> impl IntoResponse {
+    _user: Editor,
+    lang: Lang,
+    ValidatedJson(payload): ValidatedJson<RequestWord>,
+) -> Result<impl IntoResponse, AppError> {
     let query = r#"
-        INSERT INTO word (term, definition, lesson_id)
-        VALUES ($1, $2, $3)
-        RETURNING id, term, definition, lesson_id
+        INSERT INTO word (term, lesson_id, root, pattern,
+                          part_of_speech, gender, dual, plural, plural_type, past, present, masdar)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+        RETURNING id, term, lesson_id, root, pattern,
+                  part_of_speech, gender, dual, plural, plural_type, past, present, masdar
     "#;
 
-    let result = sqlx::query_as::<_, Word>(query)
+    let mut tx = state.db_pool.begin().await?;
+
+    let mut word = sqlx::query_as::<_, Word>(query)
         .bind(&payload.term)
-        .bind(&payload.definition)
-        .bind(&payload.lesson_id)
-        .fetch_one(&state.db_pool)
-        .await;
-
-    match result {
-        Ok(word) => (StatusCode::CREATED, AnswerJson(word)).into_response(),
-        Err(err) => {
-            eprint!("Failed to create word: {:?}", err);
-            StatusCode::INTERNAL_SERVER_ERROR.into_response()
-        }
-    }
+        .bind(payload.lesson_id)
+        .bind(payload.root.as_deref().and_then(canonical_root))
+        .bind(&payload.pattern)
+        .bind(payload.part_of_speech)
+        .bind(payload.gender)
+        .bind(&payload.dual)
+        .bind(&payload.plural)
+        .bind(payload.plural_type)
+        .bind(&payload.past)
+        .bind(&payload.present)
+        .bind(&payload.masdar)
+        .fetch_one(&mut *tx)
+        .await?;
+
+    save_translations(&mut tx, word.id, &collect_translations(&payload.definition, &payload.translations)).await?;
+
+    tx.commit().await?;
+
+    attach_definitions(&state.db_pool, std::slice::from_mut(&mut word), &lang).await?;
+
+    let suggestion = analyze(&word.term).map(Into::into);
+
+    Ok((StatusCode::CREATED, AnswerJson(CreatedWord { word, suggestion })))
 }
 
 pub async fn update_word_put(
-    Path(lesson_id): Path<i32>,
+    Path(word_id): Path<i32>,
     State(state): State<AppState>,
-    Json(payload): Json<RequestWord>,
-) -> impl IntoResponse {
+    _user: Editor,
+    lang: Lang,
+    ValidatedJson(payload): ValidatedJson<RequestWord>,
+) -> Result<impl IntoResponse, AppError> {
     let query = r#"
         UPDATE word
-        SET term = $1, definition = $2, lesson_id = $3
-        WHERE id = $4
-        RETURNING id, term, definition, lesson_id
+        SET term = $1, lesson_id = $2, root = $3, pattern = $4,
+            part_of_speech = $5, gender = $6, dual = $7, plural = $8, plural_type = $9,
+            past = $10, present = $11, masdar = $12
+        WHERE id = $13
+        RETURNING id, term, lesson_id, root, pattern,
+                  part_of_speech, gender, dual, plural, plural_type, past, present, masdar
     "#;
 
-    let result = sqlx::query_as::<_, Word>(query)
+    let mut tx = state.db_pool.begin().await?;
+
+    let mut word = sqlx::query_as::<_, Word>(query)
         .bind(&payload.term)
-        .bind(&payload.definition)
-        .bind(&payload.lesson_id)
-        .bind(lesson_id)
-        .fetch_optional(&state.db_pool)
-        .await;
-
-    match result {
-        Ok(Some(lesson)) => Ok(AnswerJson(lesson)),
-        Ok(None) => Err((
-            StatusCode::NOT_FOUND,
-            format!("Lesson with id {} not found", lesson_id),
-        )),
-        Err(err) => {
-            eprintln!("Failed to update lesson: {:?}", err);
-            Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "Failed to update lesson".to_string(),
-            ))
-        }
-    }
+        .bind(payload.lesson_id)
+        .bind(payload.root.as_deref().and_then(canonical_root))
+        .bind(&payload.pattern)
+        .bind(payload.part_of_speech)
+        .bind(payload.gender)
+        .bind(&payload.dual)
+        .bind(&payload.plural)
+        .bind(payload.plural_type)
+        .bind(&payload.past)
+        .bind(&payload.present)
+        .bind(&payload.masdar)
+        .bind(word_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Word with id {} not found", word_id)))?;
+
+    // Переводы из запроса добавляются или заменяются, остальные языки не трогаем
+    save_translations(&mut tx, word.id, &collect_translations(&payload.definition, &payload.translations)).await?;
+
+    tx.commit().await?;
+
+    attach_definitions(&state.db_pool, std::slice::from_mut(&mut word), &lang).await?;
+
+    Ok(AnswerJson(word))
 }
 
-pub async fn delete_word(State(state): State<AppState>, Path(id): Path<i32>) -> impl IntoResponse {
+pub async fn delete_word(
+    State(state): State<AppState>,
+    _user: Editor,
+    Path(id): Path<i32>,
+) -> Result<impl IntoResponse, AppError> {
     let query = r#"
         DELETE FROM word
         WHERE id = $1
         RETURNING id
     "#;
 
-    let result = sqlx::query(query)
+    let mut tx = state.db_pool.begin().await?;
+
+    // Записи об аудио удалятся каскадом, а файлы из хранилища убираем сами
+    let audio_keys = sqlx::query_scalar::<_, String>("SELECT storage_key FROM audio_file WHERE word_id = $1")
         .bind(id)
-        .fetch_optional(&state.db_pool)
-        .await;
-
-    match result {
-        Ok(Some(_)) => StatusCode::OK.into_response(),
-        Ok(None) => StatusCode::NOT_FOUND.into_response(),
-        Err(err) => {
-            eprint!("Failed to delete word: {:?}", err);
-            StatusCode::INTERNAL_SERVER_ERROR.into_response()
-        }
-    }
+        .fetch_all(&mut *tx)
+        .await?;
+
+    sqlx::query(query)
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| AppError::from_delete(e, "Word"))?
+        .ok_or_else(|| AppError::not_found(format!("Word with id {} not found", id)))?;
+
+    tx.commit().await?;
+
+    remove_stored_files(state.storage.as_ref(), &audio_keys).await;
+
+    Ok(StatusCode::OK)
 }
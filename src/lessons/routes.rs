@@ -2,10 +2,17 @@
 // Lesson	/lessons	/lessons/{id}/words
 // Word	/words	или через /lessons/{id}/words для вложений
 
-use axum::{routing::get, Router};
+use axum::extract::DefaultBodyLimit;
+use axum::{routing::{get, post, put}, Router};
 
 use super::state::AppState;
-use crate::handlers::{lesson::*, textbook::*, word::*};
+use crate::auth::routes::auth_router;
+use crate::handlers::{analyze::*, audio::*, example::*, lesson::*, progress::*, quiz::*, review::*, root::*, textbook::*, translation::*, transliterate::*, word::*};
+
+/// Лимит тела для загрузки аудио: сам файл плюс служебные части multipart.
+fn audio_body_limit() -> DefaultBodyLimit {
+    DefaultBodyLimit::max(audio_max_bytes() + 64 * 1024)
+}
 
 async fn root() -> &'static str {
     "Arabic API"
@@ -14,15 +21,47 @@ async fn root() -> &'static str {
 pub fn create_router(state: AppState) -> Router {
     Router::new()
         .route("/api/v1/", get(root))
+        //----------------------------------auth---------------------------------------------------
+        .nest("/api/v1/auth", auth_router())
         //-------------------------------textbooks-------------------------------------------------
         .route("/api/v1/textbooks", get(get_all_textbooks).post(create_textbook),)
         .route("/api/v1/textbooks/{id}", get(get_textbook).put(update_textbook).delete(delete_textbook),)
+        .route("/api/v1/textbooks/{id}/lessons", get(get_textbook_lessons))
+        .route("/api/v1/textbooks/{id}/tree", get(get_textbook_tree))
+        .route("/api/v1/textbooks/{id}/roots", get(get_textbook_roots))
+        .route("/api/v1/textbooks/{id}/lessons/order", put(reorder_lessons))
         //-------------------------------lessons---------------------------------------------------
         .route("/api/v1/lessons", get(get_lessons).post(create_lesson))
+        .route("/api/v1/lessons/search", get(search_lessons))
         .route("/api/v1/lessons/{id}", get(get_leson).patch(update_lesson_patch).delete(delete_lesson),)
         .route("/api/v1/lessons/{id}/words", get(get_all_word_for_lesson).post(add_word_to_lesson),)
+        .route("/api/v1/lessons/{id}/annotated", get(get_annotated_lesson))
+        .route("/api/v1/lessons/{id}/unknown-words", get(get_unknown_words))
+        .route("/api/v1/lessons/{id}/quiz", get(get_lesson_quiz))
+        .route("/api/v1/lessons/{id}/quiz/grade", post(grade_lesson_quiz))
+        .route("/api/v1/lessons/{id}/audio", get(get_lesson_audio).post(upload_lesson_audio).delete(delete_lesson_audio).layer(audio_body_limit()),)
         //----------------------------------word---------------------------------------------------
         .route("/api/v1/words", get(get_words).post(create_word))
+        .route("/api/v1/words/search", get(search_words))
         .route("/api/v1/words/{id}", get(get_word).put(update_word_put).delete(delete_word),)
+        .route("/api/v1/words/{id}/translations", get(get_word_translations))
+        .route("/api/v1/words/{id}/translations/{lang}", put(put_word_translation).delete(delete_word_translation),)
+        .route("/api/v1/words/{id}/audio", get(get_word_audio).post(upload_word_audio).delete(delete_word_audio).layer(audio_body_limit()),)
+        .route("/api/v1/words/{id}/examples", get(get_word_examples).post(create_word_example))
+        .route("/api/v1/words/{id}/examples/{example_id}", get(get_word_example).put(update_word_example).delete(delete_word_example),)
+        //----------------------------------roots--------------------------------------------------
+        .route("/api/v1/roots/{root}/words", get(get_words_by_root))
+        //--------------------------------progress-------------------------------------------------
+        .route("/api/v1/me/progress", get(get_my_progress))
+        .route("/api/v1/me/progress/{lesson_id}", put(update_my_progress).delete(delete_my_progress),)
+        .route("/api/v1/users/{id}/progress", get(get_user_progress))
+        //--------------------------------reviews--------------------------------------------------
+        .route("/api/v1/reviews/due", get(get_due_reviews))
+        .route("/api/v1/reviews/{word_id}", post(review_word))
+        //--------------------------------analysis-------------------------------------------------
+        .route("/api/v1/analyze", get(analyze_term))
+        .route("/api/v1/answers/check", post(check_answer))
+        .route("/api/v1/transliterate", get(transliterate_text))
+        .route("/api/v1/transliterate/arabic", get(buckwalter_to_arabic))
         .with_state(state)
 }
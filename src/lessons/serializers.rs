@@ -1,7 +1,17 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 // use chrono::NaiveDateTime;
-use sqlx::types::chrono::NaiveDateTime;
+use sqlx::types::chrono::{DateTime, NaiveDateTime, Utc};
+use validator::{Validate, ValidationError};
+
+use crate::arabic::grading::Strictness;
+use crate::arabic::stemmer::Analysis;
+use crate::arabic::transliterate::{transliterate, Scheme};
+use crate::quiz::{Answer, QuizItem, QuizWord};
+use crate::srs::{Grade, ReviewState};
+use crate::utils::validation::{
+    arabic_root, arabic_script, arabic_sentence, has_definition, language_code, not_blank, plural_forms, verb_forms, web_url,
+};
 
 #[derive(Serialize, FromRow)]
 pub struct Textbook {
@@ -18,51 +28,580 @@ pub struct Lesson {
     pub video_url: Option<String>,
     pub created_at: NaiveDateTime,
     pub textbook_id: i32,
+    /// Порядковый номер урока в учебнике
+    pub position: i32,
+    /// Пройден ли урок текущим пользователем; только для авторизованных запросов
+    #[sqlx(skip)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completed: Option<bool>,
+}
+
+/// Урок вместе с соседями по учебнику для навигации "назад/вперёд".
+#[derive(Serialize, FromRow)]
+pub struct LessonDetail {
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub lesson: Lesson,
+    pub prev: Option<i32>,
+    pub next: Option<i32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "part_of_speech", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum PartOfSpeech {
+    Noun,
+    Verb,
+    Adjective,
+    Adverb,
+    Pronoun,
+    Preposition,
+    Particle,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "grammatical_gender", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum Gender {
+    Masculine,
+    Feminine,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "plural_type", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum PluralType {
+    SoundMasculine,
+    SoundFeminine,
+    Broken,
 }
 
 #[derive(Serialize, FromRow)]
 pub struct Word {
     pub id: i32,
     pub term: String,
+    /// Перевод на предпочитаемом клиентом языке, подставляется из word_translation
+    #[sqlx(skip)]
     pub definition: String,
+    /// Язык, на котором пришёл `definition`
+    #[sqlx(skip)]
+    pub language: Option<String>,
     pub lesson_id: i32,
+    pub root: Option<String>,
+    pub pattern: Option<String>,
+    pub part_of_speech: Option<PartOfSpeech>,
+    pub gender: Option<Gender>,
+    /// Форма двойственного числа
+    pub dual: Option<String>,
+    /// Форма множественного числа, в том числе ломаного
+    pub plural: Option<String>,
+    pub plural_type: Option<PluralType>,
+    /// Для глаголов: прошедшее время (مَاضٍ)
+    pub past: Option<String>,
+    /// Для глаголов: настоящее время (مُضَارِع)
+    pub present: Option<String>,
+    /// Для глаголов: масдар
+    pub masdar: Option<String>,
+}
+
+/// Арабский текст записи, который транслитерируется по запросу.
+pub trait HasArabic {
+    fn arabic(&self) -> &str;
+}
+
+/// У слова транслитерируется термин.
+impl HasArabic for Word {
+    fn arabic(&self) -> &str {
+        &self.term
+    }
+}
+
+/// У урока транслитерируется текст.
+impl HasArabic for Lesson {
+    fn arabic(&self) -> &str {
+        &self.text
+    }
+}
+
+impl HasArabic for LessonDetail {
+    fn arabic(&self) -> &str {
+        &self.lesson.text
+    }
+}
+
+/// Запись с латинской транслитерацией, если её запросили через `?transliteration=`.
+#[derive(Serialize)]
+pub struct Transliterated<T> {
+    #[serde(flatten)]
+    pub item: T,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transliteration: Option<String>,
+}
+
+impl<T: HasArabic> Transliterated<T> {
+    pub fn new(item: T, scheme: Option<Scheme>) -> Self {
+        let transliteration = scheme.map(|scheme| transliterate(item.arabic(), scheme));
+        Transliterated { item, transliteration }
+    }
+
+    pub fn all(items: Vec<T>, scheme: Option<Scheme>) -> Vec<Self> {
+        items.into_iter().map(|item| Transliterated::new(item, scheme)).collect()
+    }
+}
+
+#[derive(Serialize)]
+pub struct TransliterateResult {
+    pub text: String,
+    pub result: String,
+}
+
+/// Предложенные стеммером корень и вазн
+#[derive(Serialize)]
+pub struct WordAnalysis {
+    pub root: String,
+    pub pattern: String,
+}
+
+impl From<Analysis> for WordAnalysis {
+    fn from(analysis: Analysis) -> Self {
+        WordAnalysis { root: analysis.root, pattern: analysis.pattern }
+    }
+}
+
+/// Ответ на создание слова: само слово и подсказка корня/вазна для редактора.
+#[derive(Serialize)]
+pub struct CreatedWord {
+    #[serde(flatten)]
+    pub word: Word,
+    pub suggestion: Option<WordAnalysis>,
+}
+
+#[derive(Serialize)]
+pub struct AnalyzeResult {
+    pub term: String,
+    pub suggestion: Option<WordAnalysis>,
+}
+
+#[derive(Serialize, FromRow)]
+pub struct WordExample {
+    pub id: i32,
+    pub word_id: i32,
+    /// Предложение на арабском
+    pub text: String,
+    pub translation: Option<String>,
+    /// Откуда взят пример: книга, аят, хадис и т.п.
+    pub source: Option<String>,
+    /// Урок, в тексте которого встречается предложение
+    pub lesson_id: Option<i32>,
+    pub created_at: NaiveDateTime,
+}
+
+/// Метаданные загруженного аудио; сам файл отдаётся по `/audio`
+#[derive(Serialize, FromRow)]
+pub struct AudioFile {
+    pub content_type: String,
+    pub size: i64,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Serialize, FromRow)]
+pub struct RootCount {
+    pub root: String,
+    pub word_count: i64,
+}
+
+#[derive(Serialize, FromRow)]
+pub struct LessonSearchResult {
+    pub id: i32,
+    pub title: String,
+    pub textbook_id: i32,
+    pub rank: f32,
+    /// Фрагменты текста с найденными словами, выделенными <mark>...</mark>
+    pub snippet: String,
+}
+
+/// Урок со всеми своими словами (для дерева учебника)
+#[derive(Serialize)]
+pub struct LessonTree {
+    #[serde(flatten)]
+    pub lesson: Lesson,
+    pub words: Vec<Word>,
+}
+
+/// Учебник целиком: уроки по порядку, в каждом его слова
+#[derive(Serialize)]
+pub struct TextbookTree {
+    #[serde(flatten)]
+    pub textbook: Textbook,
+    pub lessons: Vec<LessonTree>,
+}
+
+/// Состояние карточки слова у текущего пользователя
+#[derive(Serialize, FromRow)]
+pub struct WordReview {
+    pub word_id: i32,
+    pub ease: f64,
+    pub interval_days: i32,
+    pub repetitions: i32,
+    pub lapses: i32,
+    pub due_at: DateTime<Utc>,
+    pub last_reviewed_at: DateTime<Utc>,
+}
+
+impl From<&WordReview> for ReviewState {
+    fn from(review: &WordReview) -> Self {
+        ReviewState {
+            ease: review.ease,
+            interval_days: review.interval_days,
+            repetitions: review.repetitions,
+            lapses: review.lapses,
+            due_at: review.due_at,
+        }
+    }
+}
+
+/// Карточка для повторения: слово и его состояние (`null` у слова, которое ещё не учили)
+#[derive(Serialize)]
+pub struct ReviewCard {
+    #[serde(flatten)]
+    pub word: Word,
+    pub review: Option<WordReview>,
+}
+
+impl From<Word> for QuizWord {
+    fn from(word: Word) -> Self {
+        QuizWord { id: word.id, term: word.term, definition: word.definition, part_of_speech: word.part_of_speech }
+    }
+}
+
+/// Тест по уроку. Ответы не отдаются: для проверки тест восстанавливается по `seed`
+#[derive(Serialize)]
+pub struct LessonQuiz {
+    pub lesson_id: i32,
+    pub seed: u64,
+    pub items: Vec<QuizItem>,
+}
+
+/// Прогресс текущего пользователя по уроку
+#[derive(Serialize, FromRow)]
+pub struct LessonProgress {
+    pub lesson_id: i32,
+    pub lesson_title: String,
+    pub textbook_id: i32,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub time_spent_seconds: i32,
+    pub last_position: Option<i32>,
+    pub quiz_score: Option<f64>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Доля пройденных уроков учебника
+#[derive(Serialize, FromRow)]
+pub struct TextbookProgress {
+    pub textbook_id: i32,
+    pub title: String,
+    pub lessons_total: i64,
+    pub lessons_completed: i64,
+    /// Процент пройденных уроков, с точностью до десятых
+    pub percent: f64,
+}
+
+#[derive(Serialize)]
+pub struct MyProgress {
+    pub textbooks: Vec<TextbookProgress>,
+    pub lessons: Vec<LessonProgress>,
+}
+
+/// Токен текста урока для режима чтения
+#[derive(Serialize)]
+pub struct AnnotatedToken {
+    pub text: String,
+    /// Арабское слово, а не пробел или знак препинания
+    pub is_word: bool,
+    /// Слова урока, которым соответствует токен (несколько, если это омографы)
+    pub word_ids: Vec<i32>,
+}
+
+#[derive(Serialize)]
+pub struct AnnotatedLesson {
+    pub lesson_id: i32,
+    pub tokens: Vec<AnnotatedToken>,
+}
+
+/// Слово текста, которого нет в словаре урока и предыдущих уроков
+#[derive(Serialize)]
+pub struct UnknownWord {
+    /// Форма, в которой слово впервые встретилось в тексте
+    pub text: String,
+    /// Без огласовок и артикля: по ней сгруппированы вхождения
+    pub normalized: String,
+    pub count: usize,
 }
 
 // ------------------------------request-----------------------------------------------------------
 
-#[derive(Deserialize)]
+// Ограничения длины совпадают с размерами колонок в миграциях
+
+#[derive(Deserialize, Validate)]
 pub struct RequestTextbook {
+    #[validate(
+        custom(function = "not_blank"),
+        length(max = 255, message = "must be at most 255 characters")
+    )]
     pub title: String,
     pub description: Option<String>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Validate)]
 pub struct RequestLesson {
+    #[validate(
+        custom(function = "not_blank"),
+        length(max = 255, message = "must be at most 255 characters")
+    )]
     pub title: String,
+    #[validate(custom(function = "not_blank"))]
     pub text: String,
+    #[validate(custom(function = "web_url"))]
     pub video_url: Option<String>,
     pub textbook_id: i32,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Validate)]
+#[validate(schema(function = "request_word_forms"))]
 pub struct RequestWord {
+    #[validate(
+        custom(function = "not_blank"),
+        length(max = 100, message = "must be at most 100 characters"),
+        custom(function = "arabic_script")
+    )]
     pub term: String,
-    pub definition: String,
+    /// Перевод на языке по умолчанию
+    #[validate(
+        custom(function = "not_blank"),
+        length(max = 255, message = "must be at most 255 characters")
+    )]
+    pub definition: Option<String>,
+    /// Переводы на другие языки
+    #[serde(default)]
+    #[validate(nested)]
+    pub translations: Vec<RequestTranslation>,
     pub lesson_id: i32,
+    #[validate(custom(function = "arabic_root"))]
+    pub root: Option<String>,
+    #[validate(
+        length(max = 50, message = "must be at most 50 characters"),
+        custom(function = "arabic_script")
+    )]
+    pub pattern: Option<String>,
+    pub part_of_speech: Option<PartOfSpeech>,
+    pub gender: Option<Gender>,
+    #[validate(
+        length(max = 100, message = "must be at most 100 characters"),
+        custom(function = "arabic_script")
+    )]
+    pub dual: Option<String>,
+    #[validate(
+        length(max = 100, message = "must be at most 100 characters"),
+        custom(function = "arabic_script")
+    )]
+    pub plural: Option<String>,
+    pub plural_type: Option<PluralType>,
+    #[validate(
+        length(max = 100, message = "must be at most 100 characters"),
+        custom(function = "arabic_script")
+    )]
+    pub past: Option<String>,
+    #[validate(
+        length(max = 100, message = "must be at most 100 characters"),
+        custom(function = "arabic_script")
+    )]
+    pub present: Option<String>,
+    #[validate(
+        length(max = 100, message = "must be at most 100 characters"),
+        custom(function = "arabic_script")
+    )]
+    pub masdar: Option<String>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Validate)]
+#[validate(schema(function = "new_word_forms"))]
 pub struct NewWord {
+    #[validate(
+        custom(function = "not_blank"),
+        length(max = 100, message = "must be at most 100 characters"),
+        custom(function = "arabic_script")
+    )]
     pub term: String,
-    pub definition: String,
+    /// Перевод на языке по умолчанию
+    #[validate(
+        custom(function = "not_blank"),
+        length(max = 255, message = "must be at most 255 characters")
+    )]
+    pub definition: Option<String>,
+    /// Переводы на другие языки
+    #[serde(default)]
+    #[validate(nested)]
+    pub translations: Vec<RequestTranslation>,
+    #[validate(custom(function = "arabic_root"))]
+    pub root: Option<String>,
+    #[validate(
+        length(max = 50, message = "must be at most 50 characters"),
+        custom(function = "arabic_script")
+    )]
+    pub pattern: Option<String>,
+    pub part_of_speech: Option<PartOfSpeech>,
+    pub gender: Option<Gender>,
+    #[validate(
+        length(max = 100, message = "must be at most 100 characters"),
+        custom(function = "arabic_script")
+    )]
+    pub dual: Option<String>,
+    #[validate(
+        length(max = 100, message = "must be at most 100 characters"),
+        custom(function = "arabic_script")
+    )]
+    pub plural: Option<String>,
+    pub plural_type: Option<PluralType>,
+    #[validate(
+        length(max = 100, message = "must be at most 100 characters"),
+        custom(function = "arabic_script")
+    )]
+    pub past: Option<String>,
+    #[validate(
+        length(max = 100, message = "must be at most 100 characters"),
+        custom(function = "arabic_script")
+    )]
+    pub present: Option<String>,
+    #[validate(
+        length(max = 100, message = "must be at most 100 characters"),
+        custom(function = "arabic_script")
+    )]
+    pub masdar: Option<String>,
     // lesson_id берется из пути
 }
 
-// --------------------------------path method----------------------------------------------------
+fn request_word_forms(word: &RequestWord) -> Result<(), ValidationError> {
+    has_definition(&word.definition, word.translations.len())?;
+    verb_forms(word.part_of_speech, &[&word.past, &word.present, &word.masdar])?;
+    plural_forms(&word.plural, word.plural_type)
+}
+
+fn new_word_forms(word: &NewWord) -> Result<(), ValidationError> {
+    has_definition(&word.definition, word.translations.len())?;
+    verb_forms(word.part_of_speech, &[&word.past, &word.present, &word.masdar])?;
+    plural_forms(&word.plural, word.plural_type)
+}
+
+#[derive(Deserialize, Validate)]
+pub struct RequestTranslation {
+    #[validate(custom(function = "language_code"))]
+    pub lang: String,
+    #[validate(
+        custom(function = "not_blank"),
+        length(max = 255, message = "must be at most 255 characters")
+    )]
+    pub definition: String,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct RequestDefinition {
+    #[validate(
+        custom(function = "not_blank"),
+        length(max = 255, message = "must be at most 255 characters")
+    )]
+    pub definition: String,
+}
+
+#[derive(Serialize, FromRow)]
+pub struct WordTranslation {
+    pub lang: String,
+    pub definition: String,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct RequestExample {
+    #[validate(
+        custom(function = "not_blank"),
+        length(max = 1000, message = "must be at most 1000 characters"),
+        custom(function = "arabic_sentence")
+    )]
+    pub text: String,
+    #[validate(length(max = 1000, message = "must be at most 1000 characters"))]
+    pub translation: Option<String>,
+    #[validate(length(max = 255, message = "must be at most 255 characters"))]
+    pub source: Option<String>,
+    pub lesson_id: Option<i32>,
+}
+
+/// Новый порядок уроков учебника: все его уроки, каждый ровно один раз.
+#[derive(Deserialize, Validate)]
+pub struct RequestLessonOrder {
+    #[validate(length(min = 1, message = "must not be empty"))]
+    pub lesson_ids: Vec<i32>,
+}
+
+#[derive(Deserialize)]
+pub struct RequestReview {
+    pub grade: Grade,
+}
+
+#[derive(Deserialize)]
+pub struct QuizAnswer {
+    /// Номер задания в тесте
+    pub item: usize,
+    pub answer: Answer,
+}
+
+/// Ответы на тест. `seed` и язык (`?lang=` / Accept-Language) должны быть те же, что при получении теста
 #[derive(Deserialize)]
+pub struct RequestQuizGrade {
+    pub seed: u64,
+    pub answers: Vec<QuizAnswer>,
+    /// Строгость проверки напечатанных ответов
+    #[serde(default)]
+    pub strictness: Strictness,
+}
+
+/// Проверка напечатанного ответа на арабском без привязки к тесту
+#[derive(Deserialize, Validate)]
+pub struct RequestAnswerCheck {
+    #[validate(
+        custom(function = "not_blank"),
+        length(max = 255, message = "must be at most 255 characters")
+    )]
+    pub expected: String,
+    #[validate(length(max = 255, message = "must be at most 255 characters"))]
+    pub answer: String,
+    #[serde(default)]
+    pub strictness: Strictness,
+}
+
+/// Отметка о занятии уроком. Переданные поля обновляются, остальные остаются как были
+#[derive(Deserialize, Validate)]
+pub struct RequestProgress {
+    /// Сколько секунд добавить к времени, проведённому в уроке
+    #[validate(range(min = 0, max = 86400, message = "must be between 0 and 86400"))]
+    pub add_seconds: Option<i32>,
+    #[validate(range(min = 0, message = "must not be negative"))]
+    pub last_position: Option<i32>,
+    /// `true` отмечает урок пройденным, `false` снимает отметку
+    pub completed: Option<bool>,
+    #[validate(range(min = 0.0, max = 1.0, message = "must be between 0 and 1"))]
+    pub quiz_score: Option<f64>,
+}
+
+// --------------------------------path method----------------------------------------------------
+#[derive(Deserialize, Validate)]
 pub struct PatchLesson {
+    #[validate(
+        custom(function = "not_blank"),
+        length(max = 255, message = "must be at most 255 characters")
+    )]
     pub title: Option<String>,
+    #[validate(custom(function = "not_blank"))]
     pub text: Option<String>,
+    #[validate(custom(function = "web_url"))]
     pub video_url: Option<String>,
     pub textbook_id: Option<i32>,
 }
@@ -1,6 +1,13 @@
-use sqlx::{query_as, Error, PgPool};
+use std::sync::Arc;
+
+use sqlx::PgPool;
+
+use crate::mailer::Mailer;
+use crate::storage::Storage;
 
 #[derive(Clone)]
 pub struct AppState {
     pub db_pool: PgPool,
+    pub mailer: Arc<dyn Mailer>,
+    pub storage: Arc<dyn Storage>,
 }
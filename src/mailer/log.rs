@@ -0,0 +1,46 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use tokio::io::AsyncWriteExt;
+
+use super::{Email, Mailer, MailerError};
+
+/// Ничего не отправляет: печатает письмо в stderr или дописывает его в файл.
+pub struct LogMailer {
+    outbox: Option<PathBuf>,
+}
+
+impl LogMailer {
+    pub fn new(outbox: Option<PathBuf>) -> Self {
+        LogMailer { outbox }
+    }
+}
+
+#[async_trait]
+impl Mailer for LogMailer {
+    async fn send(&self, email: Email) -> Result<(), MailerError> {
+        let entry = format!(
+            "To: {}\nSubject: {}\n\n{}\n----------------------------------------\n",
+            email.to, email.subject, email.body
+        );
+
+        match &self.outbox {
+            Some(path) => {
+                let mut file = tokio::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .await
+                    .map_err(|e| MailerError(format!("Failed to open outbox: {}", e)))?;
+
+                file.write_all(entry.as_bytes())
+                    .await
+                    .map_err(|e| MailerError(format!("Failed to write outbox: {}", e)))
+            }
+            None => {
+                eprintln!("{}", entry);
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,35 @@
+pub mod log;
+pub mod smtp;
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+pub struct Email {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+#[derive(Debug)]
+pub struct MailerError(pub String);
+
+impl std::fmt::Display for MailerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Отправка писем. Реализация выбирается при старте приложения.
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    async fn send(&self, email: Email) -> Result<(), MailerError>;
+}
+
+/// SMTP, если задан SMTP_HOST, иначе письма пишутся в лог/файл (для локальной разработки и тестов).
+pub fn mailer_from_env() -> Arc<dyn Mailer> {
+    match std::env::var("SMTP_HOST") {
+        Ok(host) => Arc::new(smtp::SmtpMailer::from_env(&host).expect("Invalid SMTP configuration")),
+        Err(_) => Arc::new(log::LogMailer::new(std::env::var("MAIL_OUTBOX").ok().map(Into::into))),
+    }
+}
@@ -0,0 +1,56 @@
+use async_trait::async_trait;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+
+use super::{Email, Mailer, MailerError};
+
+pub struct SmtpMailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpMailer {
+    /// Настройки: SMTP_HOST, SMTP_USERNAME, SMTP_PASSWORD, MAIL_FROM.
+    pub fn from_env(host: &str) -> Result<Self, MailerError> {
+        let from = std::env::var("MAIL_FROM")
+            .map_err(|_| MailerError("MAIL_FROM must be set".to_string()))?
+            .parse::<Mailbox>()
+            .map_err(|e| MailerError(format!("Invalid MAIL_FROM: {}", e)))?;
+
+        let mut builder = AsyncSmtpTransport::<Tokio1Executor>::relay(host)
+            .map_err(|e| MailerError(format!("Invalid SMTP host: {}", e)))?;
+
+        if let (Ok(username), Ok(password)) = (std::env::var("SMTP_USERNAME"), std::env::var("SMTP_PASSWORD")) {
+            builder = builder.credentials(Credentials::new(username, password));
+        }
+
+        Ok(SmtpMailer {
+            transport: builder.build(),
+            from,
+        })
+    }
+}
+
+#[async_trait]
+impl Mailer for SmtpMailer {
+    async fn send(&self, email: Email) -> Result<(), MailerError> {
+        let to = email
+            .to
+            .parse::<Mailbox>()
+            .map_err(|e| MailerError(format!("Invalid recipient: {}", e)))?;
+
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(email.subject)
+            .body(email.body)
+            .map_err(|e| MailerError(format!("Failed to build message: {}", e)))?;
+
+        self.transport
+            .send(message)
+            .await
+            .map(|_| ())
+            .map_err(|e| MailerError(format!("SMTP error: {}", e)))
+    }
+}
@@ -1,13 +1,20 @@
-use axum::http::header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE};
+use std::net::SocketAddr;
+
+use axum::http::header::{ACCEPT, ACCEPT_RANGES, AUTHORIZATION, CONTENT_TYPE, RANGE};
 use axum::http::Method;
 use axum::http::{HeaderName, HeaderValue};
 use sqlx::PgPool;
 use tower_http::cors::CorsLayer;
 
+mod arabic;
 mod handlers;
 mod lessons;
 mod utils;
 mod auth;
+mod mailer;
+mod quiz;
+mod srs;
+mod storage;
 
 use lessons::routes::create_router;
 use lessons::state::AppState;
@@ -30,13 +37,19 @@ async fn main() {
             Method::DELETE,
             Method::PATCH,
         ])
-        .allow_headers([CONTENT_TYPE, ACCEPT, AUTHORIZATION])
+        .allow_headers([CONTENT_TYPE, ACCEPT, AUTHORIZATION, RANGE])
         .allow_credentials(true)
-        .expose_headers([HeaderName::from_static("content-range")]);
+        .expose_headers([HeaderName::from_static("content-range"), ACCEPT_RANGES]);
 
-    let app = create_router(AppState { db_pool: db_pool }).layer(cors);
+    let app = create_router(AppState {
+        db_pool,
+        mailer: mailer::mailer_from_env(),
+        storage: storage::storage_from_env(),
+    }).layer(cors);
 
     println!("Server running on http://0.0.0.0:2000");
     let listener = tokio::net::TcpListener::bind("0.0.0.0:2000").await.unwrap();
-    axum::serve(listener, app).await.unwrap();
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
+        .await
+        .unwrap();
 }
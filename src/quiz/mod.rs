@@ -0,0 +1,465 @@
+//! Тесты по словам урока: выбор варианта, сопоставление и пропуск в предложении.
+//! Тест полностью определяется словами, текстом урока и `seed`, поэтому для проверки
+//! его не нужно хранить — достаточно сгенерировать заново с тем же `seed`.
+//! Генератор — ChaCha8: в отличие от `StdRng`, его последовательность не меняется
+//! между версиями `rand`, и выданный раньше тест после обновления проверяется верно.
+
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::arabic::grading::{grade_answer, Difference, Strictness};
+use crate::arabic::normalize::normalize;
+use crate::arabic::tokenize::{tokenize, Lexicon, Token, TokenKind};
+use crate::lessons::serializers::PartOfSpeech;
+
+/// Вариантов ответа в вопросе с выбором (вместе с правильным).
+const OPTIONS: usize = 4;
+
+/// Сколько пар слово–перевод в задании на сопоставление.
+const MATCHING_PAIRS: usize = 5;
+
+const BLANK: &str = "____";
+
+/// Символы, на которых заканчивается предложение.
+const SENTENCE_ENDS: &[char] = &['.', '!', '?', '؟', '؛', '\n'];
+
+/// Слово урока или учебника с переводом на языке пользователя.
+#[derive(Debug, Clone)]
+pub struct QuizWord {
+    pub id: i32,
+    pub term: String,
+    pub definition: String,
+    pub part_of_speech: Option<PartOfSpeech>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    /// Дано арабское слово, выбрать перевод
+    ArabicToDefinition,
+    /// Дан перевод, выбрать арабское слово
+    DefinitionToArabic,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum QuizItem {
+    MultipleChoice {
+        word_id: i32,
+        direction: Direction,
+        prompt: String,
+        options: Vec<String>,
+    },
+    /// Каждому термину сопоставить одно из определений
+    Matching {
+        terms: Vec<String>,
+        definitions: Vec<String>,
+    },
+    /// Предложение из текста урока, где слово заменено на `____`; подсказка — перевод
+    FillBlank {
+        word_id: i32,
+        sentence: String,
+        hint: String,
+        /// Словарная форма слова: её тоже принимаем как ответ
+        #[serde(skip)]
+        term: String,
+    },
+}
+
+/// Ответ на задание: номер варианта, номера определений для терминов по порядку или текст.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Answer {
+    Choice(usize),
+    Matching(Vec<usize>),
+    Text(String),
+}
+
+/// Задания и правильные ответы к ним (по тем же индексам).
+pub struct Quiz {
+    pub items: Vec<QuizItem>,
+    pub answers: Vec<Answer>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ItemResult {
+    pub item: usize,
+    /// От 0 до 1; в сопоставлении засчитывается доля верных пар
+    pub score: f64,
+    pub expected: Answer,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub differences: Vec<Difference>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QuizResult {
+    /// Средний балл по всем заданиям, пропущенные считаются неверными
+    pub score: f64,
+    pub items: Vec<ItemResult>,
+}
+
+fn definition_key(text: &str) -> String {
+    text.trim().to_lowercase()
+}
+
+fn term_key(text: &str) -> String {
+    normalize(text.trim())
+}
+
+/// До `OPTIONS - 1` неправильных вариантов из слов учебника: сначала той же части речи,
+/// затем любые. Варианты, совпадающие с правильным ответом или друг с другом, отбрасываются.
+fn distractors(
+    word: &QuizWord,
+    pool: &[QuizWord],
+    rng: &mut ChaCha8Rng,
+    text: fn(&QuizWord) -> &str,
+    key: fn(&str) -> String,
+) -> Vec<String> {
+    let (mut same, mut other): (Vec<&QuizWord>, Vec<&QuizWord>) = pool
+        .iter()
+        .filter(|w| w.id != word.id)
+        .partition(|w| w.part_of_speech.is_some() && w.part_of_speech == word.part_of_speech);
+
+    same.shuffle(rng);
+    other.shuffle(rng);
+
+    let mut seen = vec![key(text(word))];
+    let mut result = Vec::new();
+
+    for candidate in same.into_iter().chain(other) {
+        if result.len() == OPTIONS - 1 {
+            break;
+        }
+        let candidate_key = key(text(candidate));
+        if seen.contains(&candidate_key) {
+            continue;
+        }
+        seen.push(candidate_key);
+        result.push(text(candidate).to_string());
+    }
+
+    result
+}
+
+fn multiple_choice(word: &QuizWord, pool: &[QuizWord], rng: &mut ChaCha8Rng) -> Option<(QuizItem, Answer)> {
+    let direction = if rng.gen_bool(0.5) { Direction::ArabicToDefinition } else { Direction::DefinitionToArabic };
+
+    let (prompt, correct, wrong) = match direction {
+        Direction::ArabicToDefinition => {
+            let wrong = distractors(word, pool, rng, |w| &w.definition, definition_key);
+            (&word.term, &word.definition, wrong)
+        }
+        Direction::DefinitionToArabic => {
+            let wrong = distractors(word, pool, rng, |w| &w.term, term_key);
+            (&word.definition, &word.term, wrong)
+        }
+    };
+
+    // Без единого неправильного варианта выбирать не из чего
+    if wrong.is_empty() {
+        return None;
+    }
+
+    let mut options = wrong;
+    options.push(correct.clone());
+    options.shuffle(rng);
+    let answer = options.iter().position(|option| option == correct)?;
+
+    let item = QuizItem::MultipleChoice { word_id: word.id, direction, prompt: prompt.clone(), options };
+    Some((item, Answer::Choice(answer)))
+}
+
+fn matching(words: &[QuizWord], rng: &mut ChaCha8Rng) -> Option<(QuizItem, Answer)> {
+    let mut chosen: Vec<&QuizWord> = Vec::new();
+    for word in words {
+        if chosen.len() == MATCHING_PAIRS {
+            break;
+        }
+        // Одинаковые термины или переводы сделали бы сопоставление неоднозначным
+        if chosen.iter().any(|w| {
+            term_key(&w.term) == term_key(&word.term) || definition_key(&w.definition) == definition_key(&word.definition)
+        }) {
+            continue;
+        }
+        chosen.push(word);
+    }
+
+    if chosen.len() < 2 {
+        return None;
+    }
+
+    let mut order: Vec<usize> = (0..chosen.len()).collect();
+    order.shuffle(rng);
+
+    let terms = chosen.iter().map(|w| w.term.clone()).collect();
+    let definitions = order.iter().map(|&i| chosen[i].definition.clone()).collect();
+    // Для i-го термина правильное определение стоит там, куда перемешивание поставило i
+    let answer = (0..chosen.len()).map(|i| order.iter().position(|&j| j == i).unwrap_or(0)).collect();
+
+    Some((QuizItem::Matching { terms, definitions }, Answer::Matching(answer)))
+}
+
+fn sentences(tokens: &[Token]) -> Vec<&[Token]> {
+    let mut result = Vec::new();
+    let mut start = 0;
+
+    for (i, token) in tokens.iter().enumerate() {
+        if token.kind == TokenKind::Other && token.text.contains(SENTENCE_ENDS) {
+            result.push(&tokens[start..=i]);
+            start = i + 1;
+        }
+    }
+    if start < tokens.len() {
+        result.push(&tokens[start..]);
+    }
+
+    result
+}
+
+/// Первое предложение текста, где встречается слово; само слово заменяется пропуском.
+/// Токены, подходящие сразу к нескольким словам урока (омографы вроде كتب), не используются:
+/// по подсказке нельзя понять, какое из слов имелось в виду.
+fn fill_blank(word: &QuizWord, sentences: &[&[Token]], lexicon: &Lexicon) -> Option<(QuizItem, Answer)> {
+    for sentence in sentences {
+        let Some(position) = sentence.iter().position(|token| {
+            token.kind == TokenKind::Word && lexicon.lookup(&token.text) == Some(&[word.id][..])
+        }) else {
+            continue;
+        };
+
+        let text: String = sentence
+            .iter()
+            .enumerate()
+            .map(|(i, token)| if i == position { BLANK } else { token.text.as_str() })
+            .collect();
+
+        let item = QuizItem::FillBlank {
+            word_id: word.id,
+            sentence: text.trim().to_string(),
+            hint: word.definition.clone(),
+            term: word.term.clone(),
+        };
+        return Some((item, Answer::Text(sentence[position].text.clone())));
+    }
+
+    None
+}
+
+/// Тест по словам урока `words`; неправильные варианты берутся из `pool` (слова учебника),
+/// предложения с пропусками — из текста урока.
+pub fn generate(words: &[QuizWord], pool: &[QuizWord], text: &str, seed: u64) -> Quiz {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let mut quiz = Quiz { items: Vec::new(), answers: Vec::new() };
+
+    let mut shuffled: Vec<QuizWord> = words.to_vec();
+    shuffled.shuffle(&mut rng);
+
+    let mut push = |generated: Option<(QuizItem, Answer)>| {
+        if let Some((item, answer)) = generated {
+            quiz.items.push(item);
+            quiz.answers.push(answer);
+        }
+    };
+
+    for word in &shuffled {
+        push(multiple_choice(word, pool, &mut rng));
+    }
+
+    push(matching(&shuffled, &mut rng));
+
+    let mut lexicon = Lexicon::new();
+    for word in words {
+        lexicon.insert(&word.term, word.id);
+    }
+    let tokens = tokenize(text);
+    let sentences = sentences(&tokens);
+
+    for word in &shuffled {
+        push(fill_blank(word, &sentences, &lexicon));
+    }
+
+    quiz
+}
+
+/// Балл за ответ и, для напечатанного ответа, расхождения с правильным.
+fn score(expected: &Answer, given: &Answer, strictness: Strictness) -> (f64, Vec<Difference>) {
+    match (expected, given) {
+        (Answer::Choice(expected), Answer::Choice(given)) => (f64::from(u8::from(expected == given)), Vec::new()),
+        (Answer::Matching(expected), Answer::Matching(given)) => {
+            let correct = expected.iter().zip(given).filter(|(e, g)| e == g).count();
+            (correct as f64 / expected.len() as f64, Vec::new())
+        }
+        (Answer::Text(expected), Answer::Text(given)) => {
+            let grading = grade_answer(expected, given, strictness);
+            (grading.score, grading.differences)
+        }
+        _ => (0.0, Vec::new()),
+    }
+}
+
+/// Балл за ответ на задание. В пропуск можно вписать как слово из текста со всеми
+/// приставками и окончаниями, так и его словарную форму; засчитывается лучший вариант.
+fn score_item(item: &QuizItem, expected: &Answer, given: &Answer, strictness: Strictness) -> (f64, Vec<Difference>, Answer) {
+    let (score, differences) = score(expected, given, strictness);
+
+    if let (QuizItem::FillBlank { term, .. }, Answer::Text(text)) = (item, given) {
+        let grading = grade_answer(term, text, strictness);
+        if grading.score > score {
+            return (grading.score, grading.differences, Answer::Text(term.clone()));
+        }
+    }
+
+    (score, differences, expected.clone())
+}
+
+/// Проверяет ответы `(номер задания, ответ)`. Ответ не того вида, что задание, неверен.
+/// Напечатанные ответы сравниваются со строгостью `strictness`.
+pub fn grade(quiz: &Quiz, responses: &[(usize, Answer)], strictness: Strictness) -> QuizResult {
+    let items: Vec<ItemResult> = quiz
+        .items
+        .iter()
+        .zip(&quiz.answers)
+        .enumerate()
+        .map(|(index, (item, expected))| {
+            let (score, differences, expected) = responses
+                .iter()
+                .find(|(i, _)| *i == index)
+                .map(|(_, given)| score_item(item, expected, given, strictness))
+                .unwrap_or_else(|| (0.0, Vec::new(), expected.clone()));
+            ItemResult { item: index, score, expected, differences }
+        })
+        .collect();
+
+    let total = if items.is_empty() { 0.0 } else { items.iter().map(|r| r.score).sum::<f64>() / items.len() as f64 };
+
+    QuizResult { score: total, items }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(id: i32, term: &str, definition: &str, part_of_speech: Option<PartOfSpeech>) -> QuizWord {
+        QuizWord { id, term: term.to_string(), definition: definition.to_string(), part_of_speech }
+    }
+
+    fn words() -> Vec<QuizWord> {
+        vec![
+            word(1, "كِتَاب", "книга", Some(PartOfSpeech::Noun)),
+            word(2, "مَدْرَسَة", "школа", Some(PartOfSpeech::Noun)),
+            word(3, "بَيْت", "дом", Some(PartOfSpeech::Noun)),
+            word(4, "قَلَم", "ручка", Some(PartOfSpeech::Noun)),
+            word(5, "ذَهَبَ", "пошёл", Some(PartOfSpeech::Verb)),
+        ]
+    }
+
+    const TEXT: &str = "ذَهَبَ الوَلَدُ إِلَى المَدْرَسَةِ. فِي يَدِهِ الكِتَابُ وَالقَلَمُ.";
+
+    fn snapshot(quiz: &Quiz) -> String {
+        serde_json::to_string(&(&quiz.items, &quiz.answers)).unwrap()
+    }
+
+    #[test]
+    fn same_seed_gives_same_quiz() {
+        let words = words();
+        let first = generate(&words, &words, TEXT, 42);
+        let again = generate(&words, &words, TEXT, 42);
+        assert_eq!(snapshot(&first), snapshot(&again));
+
+        let differs = (0..10).any(|seed| snapshot(&generate(&words, &words, TEXT, seed)) != snapshot(&first));
+        assert!(differs);
+    }
+
+    #[test]
+    fn distractors_are_unique_and_exclude_the_answer() {
+        let target = word(1, "كِتَاب", "книга", Some(PartOfSpeech::Noun));
+        let pool = vec![
+            target.clone(),
+            word(2, "كتاب", "Книга ", Some(PartOfSpeech::Noun)),
+            word(3, "قَلَم", "ручка", Some(PartOfSpeech::Noun)),
+            word(4, "قلم", "ручка", Some(PartOfSpeech::Noun)),
+            word(5, "بَيْت", "дом", None),
+        ];
+
+        for seed in 0..20 {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            let definitions = distractors(&target, &pool, &mut rng, |w| &w.definition, definition_key);
+            assert_eq!(definitions.len(), 2, "{definitions:?}");
+            assert!(definitions.iter().all(|d| definition_key(d) != "книга"));
+            assert_ne!(definition_key(&definitions[0]), definition_key(&definitions[1]));
+
+            let terms = distractors(&target, &pool, &mut rng, |w| &w.term, term_key);
+            assert_eq!(terms.len(), 2, "{terms:?}");
+            assert!(terms.iter().all(|t| term_key(t) != term_key("كتاب")));
+        }
+    }
+
+    #[test]
+    fn multiple_choice_answer_points_to_correct_option() {
+        let words = words();
+        for seed in 0..20 {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            let Some((QuizItem::MultipleChoice { direction, options, .. }, Answer::Choice(answer))) =
+                multiple_choice(&words[0], &words, &mut rng)
+            else {
+                panic!("expected a multiple choice item");
+            };
+            let correct = match direction {
+                Direction::ArabicToDefinition => "книга",
+                Direction::DefinitionToArabic => "كِتَاب",
+            };
+            assert_eq!(options.len(), OPTIONS);
+            assert_eq!(options[answer], correct);
+        }
+    }
+
+    #[test]
+    fn matching_answer_is_the_inverse_permutation() {
+        let words = words();
+        for seed in 0..20 {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            let Some((QuizItem::Matching { terms, definitions }, Answer::Matching(answer))) = matching(&words, &mut rng) else {
+                panic!("expected a matching item");
+            };
+            for (i, term) in terms.iter().enumerate() {
+                let expected = &words.iter().find(|w| &w.term == term).unwrap().definition;
+                assert_eq!(&definitions[answer[i]], expected);
+            }
+        }
+    }
+
+    #[test]
+    fn fill_blank_accepts_text_and_dictionary_form() {
+        let words = words();
+        let quiz = generate(&words, &words, TEXT, 7);
+
+        let index = quiz
+            .items
+            .iter()
+            .position(|item| matches!(item, QuizItem::FillBlank { word_id: 2, .. }))
+            .expect("a blank for مدرسة");
+        let QuizItem::FillBlank { sentence, .. } = &quiz.items[index] else { unreachable!() };
+        assert!(sentence.contains(BLANK));
+        assert!(!sentence.contains("المَدْرَسَةِ"));
+
+        for answer in ["المَدْرَسَةِ", "المدرسة", "مَدْرَسَة", "مدرسة"] {
+            let result = grade(&quiz, &[(index, Answer::Text(answer.to_string()))], Strictness::IgnoreDiacritics);
+            assert_eq!(result.items[index].score, 1.0, "{answer}");
+        }
+
+        let result = grade(&quiz, &[(index, Answer::Text("بيت".to_string()))], Strictness::IgnoreDiacritics);
+        assert!(result.items[index].score < 1.0);
+    }
+
+    #[test]
+    fn unanswered_and_mismatched_answers_score_zero() {
+        let words = words();
+        let quiz = generate(&words, &words, TEXT, 1);
+
+        let result = grade(&quiz, &[(0, Answer::Text("كتاب".to_string()))], Strictness::default());
+        assert_eq!(result.score, 0.0);
+        assert_eq!(result.items.len(), quiz.items.len());
+    }
+}
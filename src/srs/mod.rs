@@ -0,0 +1,165 @@
+//! Интервальное повторение слов по алгоритму SM-2 (SuperMemo 2).
+//! Модуль не ходит в базу: получает текущее состояние карточки и оценку, возвращает новое состояние.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
+
+/// Начальная лёгкость карточки.
+pub const DEFAULT_EASE: f64 = 2.5;
+
+/// Ниже этой лёгкости карточка не опускается, иначе интервалы перестают расти.
+pub const MIN_EASE: f64 = 1.3;
+
+/// Интервалы после первого и второго успешного повторения подряд.
+const FIRST_INTERVAL_DAYS: i32 = 1;
+const SECOND_INTERVAL_DAYS: i32 = 6;
+
+/// Верхняя граница интервала — сто лет.
+const MAX_INTERVAL_DAYS: i32 = 36500;
+
+/// Оценка ответа, как кнопки в карточке.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Grade {
+    /// Не вспомнил
+    Again,
+    /// Вспомнил с трудом
+    Hard,
+    /// Вспомнил
+    Good,
+    /// Вспомнил сразу
+    Easy,
+}
+
+impl Grade {
+    /// Качество ответа по шкале SM-2 (0–5); меньше 3 — провал.
+    fn quality(self) -> i32 {
+        match self {
+            Grade::Again => 1,
+            Grade::Hard => 3,
+            Grade::Good => 4,
+            Grade::Easy => 5,
+        }
+    }
+}
+
+/// Состояние карточки одного пользователя для одного слова.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReviewState {
+    /// Множитель интервала (EF в SM-2)
+    pub ease: f64,
+    pub interval_days: i32,
+    /// Успешных повторений подряд
+    pub repetitions: i32,
+    /// Сколько раз выученное слово было забыто
+    pub lapses: i32,
+    pub due_at: DateTime<Utc>,
+}
+
+impl ReviewState {
+    /// Новая карточка, которую можно повторять сразу.
+    pub fn new(now: DateTime<Utc>) -> Self {
+        ReviewState { ease: DEFAULT_EASE, interval_days: 0, repetitions: 0, lapses: 0, due_at: now }
+    }
+
+    /// Состояние после ответа с оценкой `grade` в момент `now`.
+    pub fn schedule(&self, grade: Grade, now: DateTime<Utc>) -> ReviewState {
+        let quality = grade.quality();
+
+        let (ease, repetitions, interval_days, lapses) = if quality < 3 {
+            // Провал: карточка начинается заново, лёгкость, как и в SM-2, не меняется
+            let lapses = if self.repetitions > 0 { self.lapses + 1 } else { self.lapses };
+            (self.ease, 0, FIRST_INTERVAL_DAYS, lapses)
+        } else {
+            let interval_days = match self.repetitions {
+                0 => FIRST_INTERVAL_DAYS,
+                1 => SECOND_INTERVAL_DAYS,
+                _ => (self.interval_days as f64 * self.ease).round() as i32,
+            };
+            let miss = (5 - quality) as f64;
+            let ease = (self.ease + 0.1 - miss * (0.08 + miss * 0.02)).max(MIN_EASE);
+            (ease, self.repetitions + 1, interval_days.clamp(FIRST_INTERVAL_DAYS, MAX_INTERVAL_DAYS), self.lapses)
+        };
+
+        ReviewState {
+            ease,
+            interval_days,
+            repetitions,
+            lapses,
+            due_at: now + Duration::days(interval_days as i64),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2025-06-01T12:00:00Z").unwrap().with_timezone(&Utc)
+    }
+
+    fn review(grades: &[Grade]) -> ReviewState {
+        grades.iter().fold(ReviewState::new(now()), |state, &grade| state.schedule(grade, now()))
+    }
+
+    #[test]
+    fn first_intervals_are_one_and_six_days() {
+        let first = review(&[Grade::Good]);
+        assert_eq!((first.repetitions, first.interval_days), (1, 1));
+
+        let second = review(&[Grade::Good, Grade::Good]);
+        assert_eq!((second.repetitions, second.interval_days), (2, 6));
+    }
+
+    #[test]
+    fn later_intervals_grow_by_ease() {
+        let third = review(&[Grade::Good, Grade::Good, Grade::Good]);
+        // Good не меняет лёгкость: 6 * 2.5 = 15
+        assert_eq!(third.ease, DEFAULT_EASE);
+        assert_eq!((third.repetitions, third.interval_days), (3, 15));
+
+        let fourth = third.schedule(Grade::Easy, now());
+        assert_eq!(fourth.interval_days, 38);
+        assert!((fourth.ease - 2.6).abs() < 1e-9);
+    }
+
+    #[test]
+    fn hard_lowers_ease() {
+        let state = review(&[Grade::Hard]);
+        assert!((state.ease - 2.36).abs() < 1e-9);
+        assert_eq!(state.repetitions, 1);
+    }
+
+    #[test]
+    fn lapse_resets_repetitions_and_keeps_ease() {
+        let learned = review(&[Grade::Good, Grade::Good, Grade::Hard]);
+        let lapsed = learned.schedule(Grade::Again, now());
+
+        assert_eq!(lapsed.repetitions, 0);
+        assert_eq!(lapsed.interval_days, 1);
+        assert_eq!(lapsed.lapses, 1);
+        assert_eq!(lapsed.ease, learned.ease);
+    }
+
+    #[test]
+    fn failing_a_new_card_is_not_a_lapse() {
+        let state = review(&[Grade::Again, Grade::Again]);
+        assert_eq!((state.repetitions, state.lapses, state.interval_days), (0, 0, 1));
+        assert_eq!(state.ease, DEFAULT_EASE);
+    }
+
+    #[test]
+    fn ease_does_not_drop_below_minimum() {
+        let state = review(&[Grade::Hard; 20]);
+        assert_eq!(state.ease, MIN_EASE);
+    }
+
+    #[test]
+    fn due_at_is_interval_after_review() {
+        let state = review(&[Grade::Good, Grade::Good]);
+        assert_eq!(state.due_at, now() + Duration::days(6));
+
+        assert_eq!(ReviewState::new(now()).due_at, now());
+    }
+}
@@ -0,0 +1,73 @@
+use std::io::SeekFrom;
+use std::ops::Range;
+use std::path::{Component, Path, PathBuf};
+
+use async_trait::async_trait;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+use super::{Storage, StorageError, StorageReader};
+
+/// Файлы в локальном каталоге.
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    pub fn new(root: PathBuf) -> Self {
+        LocalStorage { root }
+    }
+
+    /// Ключ не должен выводить за пределы корневого каталога.
+    fn path(&self, key: &str) -> Result<PathBuf, StorageError> {
+        let relative = Path::new(key);
+        if key.is_empty() || !relative.components().all(|c| matches!(c, Component::Normal(_))) {
+            return Err(StorageError(format!("Invalid storage key: {}", key)));
+        }
+        Ok(self.root.join(relative))
+    }
+}
+
+#[async_trait]
+impl Storage for LocalStorage {
+    async fn put(&self, key: &str, data: &[u8]) -> Result<(), StorageError> {
+        let path = self.path(key)?;
+
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| StorageError(format!("Failed to create {}: {}", parent.display(), e)))?;
+        }
+
+        // Пишем во временный файл и переименовываем, чтобы читатели не увидели недописанный файл
+        let tmp = path.with_extension("part");
+        tokio::fs::write(&tmp, data)
+            .await
+            .map_err(|e| StorageError(format!("Failed to write {}: {}", tmp.display(), e)))?;
+        tokio::fs::rename(&tmp, &path)
+            .await
+            .map_err(|e| StorageError(format!("Failed to move {}: {}", path.display(), e)))
+    }
+
+    async fn read(&self, key: &str, range: Range<u64>) -> Result<StorageReader, StorageError> {
+        let path = self.path(key)?;
+
+        let mut file = tokio::fs::File::open(&path)
+            .await
+            .map_err(|e| StorageError(format!("Failed to open {}: {}", path.display(), e)))?;
+        file.seek(SeekFrom::Start(range.start))
+            .await
+            .map_err(|e| StorageError(format!("Failed to seek {}: {}", path.display(), e)))?;
+
+        Ok(Box::pin(file.take(range.end - range.start)))
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        let path = self.path(key)?;
+
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(StorageError(format!("Failed to delete {}: {}", path.display(), e))),
+        }
+    }
+}
@@ -0,0 +1,37 @@
+pub mod local;
+
+use std::ops::Range;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tokio::io::AsyncRead;
+
+#[derive(Debug)]
+pub struct StorageError(pub String);
+
+impl std::fmt::Display for StorageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+pub type StorageReader = Pin<Box<dyn AsyncRead + Send>>;
+
+/// Хранилище загруженных файлов. Ключ — относительный путь вида "words/12/3f9a0c.mp3".
+#[async_trait]
+pub trait Storage: Send + Sync {
+    async fn put(&self, key: &str, data: &[u8]) -> Result<(), StorageError>;
+
+    /// Читает байты из диапазона `range` (конец не включается).
+    async fn read(&self, key: &str, range: Range<u64>) -> Result<StorageReader, StorageError>;
+
+    /// Удаление отсутствующего файла ошибкой не считается.
+    async fn delete(&self, key: &str) -> Result<(), StorageError>;
+}
+
+/// Файлы хранятся в каталоге STORAGE_DIR (по умолчанию ./uploads).
+pub fn storage_from_env() -> Arc<dyn Storage> {
+    let dir = std::env::var("STORAGE_DIR").unwrap_or_else(|_| "uploads".to_string());
+    Arc::new(local::LocalStorage::new(dir.into()))
+}
@@ -0,0 +1,205 @@
+use axum::extract::multipart::{MultipartError, MultipartRejection};
+use axum::extract::rejection::{JsonRejection, PathRejection, QueryRejection};
+use axum::http::header::RETRY_AFTER;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Json as AnswerJson, Response};
+use serde_json::{json, Value};
+
+/// Единая ошибка для всех обработчиков.
+/// Отдаётся клиенту как `{"code": ..., "message": ..., "details": ...}`.
+#[derive(Debug)]
+pub enum AppError {
+    BadRequest(String),
+    Unauthorized(String),
+    Forbidden(String),
+    NotFound(String),
+    Conflict { message: String, details: Value },
+    PayloadTooLarge(String),
+    UnsupportedMediaType(String),
+    Unprocessable { message: String, details: Value },
+    TooManyRequests { message: String, retry_after: i64 },
+    Internal,
+}
+
+impl AppError {
+    pub fn not_found(message: impl Into<String>) -> Self {
+        AppError::NotFound(message.into())
+    }
+
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        AppError::BadRequest(message.into())
+    }
+
+    pub fn unauthorized(message: impl Into<String>) -> Self {
+        AppError::Unauthorized(message.into())
+    }
+
+    pub fn forbidden(message: impl Into<String>) -> Self {
+        AppError::Forbidden(message.into())
+    }
+
+    pub fn unprocessable(message: impl Into<String>, details: Value) -> Self {
+        AppError::Unprocessable { message: message.into(), details }
+    }
+
+    pub fn conflict(message: impl Into<String>) -> Self {
+        AppError::Conflict { message: message.into(), details: Value::Null }
+    }
+
+    /// Ошибка запроса DELETE: нарушение внешнего ключа здесь означает, что на удаляемую
+    /// запись ещё ссылаются другие, — это 409, а не «ссылка на несуществующую запись».
+    pub fn from_delete(err: sqlx::Error, what: &str) -> Self {
+        match &err {
+            sqlx::Error::Database(db_err) if db_err.code().as_deref() == Some("23503") => AppError::Conflict {
+                message: format!("{} is still referenced by other records", what),
+                details: json!({ "constraint": db_err.constraint(), "table": db_err.table() }),
+            },
+            _ => AppError::from(err),
+        }
+    }
+
+    /// Логирует исходную ошибку, клиенту уходит только общее сообщение.
+    pub fn internal(context: &str, err: impl std::fmt::Debug) -> Self {
+        eprintln!("{}: {:?}", context, err);
+        AppError::Internal
+    }
+
+    fn status(&self) -> StatusCode {
+        match self {
+            AppError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            AppError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            AppError::Forbidden(_) => StatusCode::FORBIDDEN,
+            AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::Conflict { .. } => StatusCode::CONFLICT,
+            AppError::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            AppError::UnsupportedMediaType(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            AppError::Unprocessable { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+            AppError::TooManyRequests { .. } => StatusCode::TOO_MANY_REQUESTS,
+            AppError::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn code(&self) -> &'static str {
+        match self {
+            AppError::BadRequest(_) => "bad_request",
+            AppError::Unauthorized(_) => "unauthorized",
+            AppError::Forbidden(_) => "forbidden",
+            AppError::NotFound(_) => "not_found",
+            AppError::Conflict { .. } => "conflict",
+            AppError::PayloadTooLarge(_) => "payload_too_large",
+            AppError::UnsupportedMediaType(_) => "unsupported_media_type",
+            AppError::Unprocessable { .. } => "unprocessable_entity",
+            AppError::TooManyRequests { .. } => "too_many_requests",
+            AppError::Internal => "internal_error",
+        }
+    }
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let status = self.status();
+        let code = self.code();
+
+        let retry_after = match &self {
+            AppError::TooManyRequests { retry_after, .. } => Some(*retry_after),
+            _ => None,
+        };
+
+        let (message, details) = match self {
+            AppError::BadRequest(message)
+            | AppError::Unauthorized(message)
+            | AppError::Forbidden(message)
+            | AppError::NotFound(message)
+            | AppError::PayloadTooLarge(message)
+            | AppError::UnsupportedMediaType(message)
+            | AppError::TooManyRequests { message, .. } => (message, Value::Null),
+            AppError::Conflict { message, details } | AppError::Unprocessable { message, details } => (message, details),
+            // Подробности внутренних ошибок уже записаны в лог
+            AppError::Internal => ("Internal server error".to_string(), Value::Null),
+        };
+
+        let body = AnswerJson(json!({
+            "code": code,
+            "message": message,
+            "details": details,
+        }));
+
+        match retry_after {
+            Some(secs) => (status, [(RETRY_AFTER, secs.to_string())], body).into_response(),
+            None => (status, body).into_response(),
+        }
+    }
+}
+
+impl From<sqlx::Error> for AppError {
+    fn from(err: sqlx::Error) -> Self {
+        match &err {
+            sqlx::Error::RowNotFound => AppError::not_found("Record not found"),
+            sqlx::Error::Database(db_err) => {
+                let details = json!({ "constraint": db_err.constraint() });
+
+                match db_err.code().as_deref() {
+                    // unique_violation
+                    Some("23505") => AppError::Conflict {
+                        message: "Record already exists".to_string(),
+                        details,
+                    },
+                    // foreign_key_violation; при удалении её разбирает `AppError::from_delete`
+                    Some("23503") => AppError::Unprocessable {
+                        message: "Referenced record does not exist".to_string(),
+                        details,
+                    },
+                    // not_null_violation, check_violation
+                    Some("23502") | Some("23514") => AppError::Unprocessable {
+                        message: "Invalid field value".to_string(),
+                        details,
+                    },
+                    // string_data_right_truncation
+                    Some("22001") => AppError::Unprocessable {
+                        message: "Value too long".to_string(),
+                        details: Value::Null,
+                    },
+                    _ => AppError::internal("Database error", err),
+                }
+            }
+            _ => AppError::internal("Database error", err),
+        }
+    }
+}
+
+impl From<JsonRejection> for AppError {
+    fn from(rejection: JsonRejection) -> Self {
+        match rejection.status() {
+            StatusCode::UNPROCESSABLE_ENTITY => AppError::unprocessable(rejection.body_text(), Value::Null),
+            _ => AppError::bad_request(rejection.body_text()),
+        }
+    }
+}
+
+impl From<PathRejection> for AppError {
+    fn from(rejection: PathRejection) -> Self {
+        AppError::bad_request(rejection.body_text())
+    }
+}
+
+impl From<QueryRejection> for AppError {
+    fn from(rejection: QueryRejection) -> Self {
+        AppError::bad_request(rejection.body_text())
+    }
+}
+
+impl From<MultipartRejection> for AppError {
+    fn from(rejection: MultipartRejection) -> Self {
+        AppError::bad_request(rejection.body_text())
+    }
+}
+
+impl From<MultipartError> for AppError {
+    fn from(err: MultipartError) -> Self {
+        match err.status() {
+            // Сообщение multer при превышении лимита тела ничего не говорит клиенту
+            StatusCode::PAYLOAD_TOO_LARGE => AppError::PayloadTooLarge("Request body is too large".to_string()),
+            _ => AppError::bad_request(err.body_text()),
+        }
+    }
+}
@@ -0,0 +1,53 @@
+//! Обёртки над стандартными экстракторами axum: ошибки разбора запроса
+//! отдаются в том же формате, что и остальные ошибки API.
+
+use axum::extract::{FromRequest, FromRequestParts, Request};
+use serde::de::DeserializeOwned;
+use validator::Validate;
+
+use crate::utils::error::AppError;
+
+#[derive(FromRequest)]
+#[from_request(via(axum::extract::Json), rejection(AppError))]
+pub struct Json<T>(pub T);
+
+#[derive(FromRequestParts)]
+#[from_request(via(axum::extract::Path), rejection(AppError))]
+pub struct Path<T>(pub T);
+
+#[derive(FromRequestParts)]
+#[from_request(via(axum::extract::Query), rejection(AppError))]
+pub struct Query<T>(pub T);
+
+/// multipart/form-data с ошибками разбора в формате API.
+pub struct Multipart(pub axum::extract::Multipart);
+
+impl<S> FromRequest<S> for Multipart
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let multipart = axum::extract::Multipart::from_request(req, state).await?;
+        Ok(Multipart(multipart))
+    }
+}
+
+/// Как `Json`, но перед передачей в обработчик проверяет тело запроса правилами `validator`.
+/// Ошибки валидации возвращаются как 422 со списком ошибок по каждому полю.
+pub struct ValidatedJson<T>(pub T);
+
+impl<T, S> FromRequest<S> for ValidatedJson<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(req, state).await?;
+        value.validate()?;
+        Ok(ValidatedJson(value))
+    }
+}
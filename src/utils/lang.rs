@@ -0,0 +1,92 @@
+//! Язык, на котором клиент хочет получать переводы слов.
+
+use axum::extract::FromRequestParts;
+use axum::http::header::ACCEPT_LANGUAGE;
+use axum::http::request::Parts;
+use serde::Deserialize;
+
+use crate::utils::error::AppError;
+
+/// Язык переводов по умолчанию, если клиент ничего не запросил или перевода на его язык нет.
+pub fn default_language() -> String {
+    std::env::var("DEFAULT_LANGUAGE")
+        .ok()
+        .and_then(|lang| primary_subtag(&lang))
+        .unwrap_or_else(|| "ru".to_string())
+}
+
+/// Код языка ISO 639: две или три строчные латинские буквы.
+pub fn is_language_code(code: &str) -> bool {
+    (2..=3).contains(&code.len()) && code.chars().all(|c| c.is_ascii_lowercase())
+}
+
+/// "en-US" -> "en". `None`, если это не код языка.
+fn primary_subtag(tag: &str) -> Option<String> {
+    let primary = tag.trim().split(['-', '_']).next()?.to_ascii_lowercase();
+    is_language_code(&primary).then_some(primary)
+}
+
+/// Языки из Accept-Language в порядке убывания веса q.
+fn parse_accept_language(header: &str) -> Vec<String> {
+    let mut weighted: Vec<(f32, String)> = header
+        .split(',')
+        .filter_map(|item| {
+            let mut parts = item.split(';');
+            let lang = primary_subtag(parts.next()?)?;
+            let q = parts
+                .find_map(|p| p.trim().strip_prefix("q="))
+                .and_then(|q| q.parse::<f32>().ok())
+                .unwrap_or(1.0);
+            (q > 0.0).then_some((q, lang))
+        })
+        .collect();
+
+    // Сортировка устойчивая: при равном весе сохраняется порядок из заголовка
+    weighted.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    let mut languages = Vec::new();
+    for (_, lang) in weighted {
+        if !languages.contains(&lang) {
+            languages.push(lang);
+        }
+    }
+    languages
+}
+
+#[derive(Deserialize)]
+struct LangQuery {
+    lang: Option<String>,
+}
+
+/// Предпочитаемые языки по убыванию приоритета: `?lang=`, иначе Accept-Language,
+/// в конце всегда язык по умолчанию.
+pub struct Lang(pub Vec<String>);
+
+impl<S> FromRequestParts<S> for Lang
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let axum::extract::Query(query) = axum::extract::Query::<LangQuery>::try_from_uri(&parts.uri)?;
+
+        let mut languages = match query.lang {
+            Some(lang) => vec![primary_subtag(&lang)
+                .ok_or_else(|| AppError::bad_request("Query parameter lang must be a language code, e.g. ru or en"))?],
+            None => parts
+                .headers
+                .get(ACCEPT_LANGUAGE)
+                .and_then(|value| value.to_str().ok())
+                .map(parse_accept_language)
+                .unwrap_or_default(),
+        };
+
+        let default = default_language();
+        if !languages.contains(&default) {
+            languages.push(default);
+        }
+
+        Ok(Lang(languages))
+    }
+}
@@ -1 +1,5 @@
+pub mod error;
+pub mod extract;
+pub mod lang;
 pub mod pagination;
+pub mod validation;
@@ -26,29 +26,26 @@ where
         mut builder: QueryBuilder<'a, Postgres>,
         params: &T,
     ) -> Result<PaginateResult<Self>, sqlx::Error> {
-        // Получаем SQL-запрос, чтобы подсчитать количество записей
-        let count_sql = format!("SELECT COUNT(*) FROM ({}) AS subquery", builder.sql());
-
-        // Считаем общее количество
-        let total_count: i64 = sqlx::query_scalar(&count_sql)
-            .fetch_one(db_pool)
-            .await
-            .unwrap_or(0);
-
         // Если заданы параметры пагинации
-        if let (Some(page), Some(limit)) = (params.page(), params.limit()) {
+        let paginated = if let (Some(page), Some(limit)) = (params.page(), params.limit()) {
             let offset = (page - 1) * limit;
 
-            if offset >= total_count {
-                return Ok(PaginateResult::NotFound);
-            }
-
             builder.push(" LIMIT ").push_bind(limit);
             builder.push(" OFFSET ").push_bind(offset);
-        }
+            true
+        } else {
+            false
+        };
 
         let query = builder.build_query_as::<Self>();
         let records = query.fetch_all(db_pool).await?;
+
+        // Страница за пределами выборки. Отдельный COUNT(*) здесь не делаем:
+        // builder.sql() не содержит значений параметров, привязанных через push_bind
+        if paginated && records.is_empty() {
+            return Ok(PaginateResult::NotFound);
+        }
+
         Ok(PaginateResult::Success(records))
     }
 
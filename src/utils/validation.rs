@@ -0,0 +1,191 @@
+use std::borrow::Cow;
+
+use serde_json::{Map, Value};
+use url::Url;
+use validator::{ValidationError, ValidationErrors, ValidationErrorsKind};
+
+use crate::arabic::{is_arabic_sentence, is_arabic_text};
+use crate::arabic::root::canonical_root;
+use crate::lessons::serializers::{PartOfSpeech, PluralType};
+use crate::utils::error::AppError;
+use crate::utils::lang::is_language_code;
+
+fn error(code: &'static str, message: &'static str) -> ValidationError {
+    ValidationError::new(code).with_message(Cow::Borrowed(message))
+}
+
+/// Поле не должно состоять только из пробелов.
+pub fn not_blank(value: &str) -> Result<(), ValidationError> {
+    if value.trim().is_empty() {
+        return Err(error("blank", "must not be empty"));
+    }
+    Ok(())
+}
+
+/// Слово должно быть записано арабским письмом.
+pub fn arabic_script(value: &str) -> Result<(), ValidationError> {
+    if !is_arabic_text(value) {
+        return Err(error("arabic_script", "must be written in Arabic script"));
+    }
+    Ok(())
+}
+
+/// Пример употребления: арабский текст, в котором могут быть цифры и знаки препинания.
+pub fn arabic_sentence(value: &str) -> Result<(), ValidationError> {
+    if !is_arabic_sentence(value) {
+        return Err(error("arabic_sentence", "must be written in Arabic script"));
+    }
+    Ok(())
+}
+
+/// Ссылка, которую клиент покажет пользователю: только http(s) с хостом.
+/// `javascript:` и `data:` тоже проходят `Url::parse`, поэтому схему проверяем явно.
+pub fn web_url(value: &str) -> Result<(), ValidationError> {
+    match Url::parse(value) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") && url.host().is_some() => Ok(()),
+        _ => Err(error("url", "must be an http or https URL")),
+    }
+}
+
+/// Корень из трёх или четырёх арабских букв, например "ك-ت-ب".
+pub fn arabic_root(value: &str) -> Result<(), ValidationError> {
+    if canonical_root(value).is_none() {
+        return Err(error("arabic_root", "must be an Arabic root of 3 or 4 letters, e.g. ك-ت-ب"));
+    }
+    Ok(())
+}
+
+/// Код языка перевода: "ru", "en", ...
+pub fn language_code(value: &str) -> Result<(), ValidationError> {
+    if !is_language_code(value) {
+        return Err(error("language_code", "must be a lowercase ISO 639 language code, e.g. ru or en"));
+    }
+    Ok(())
+}
+
+/// У слова должен быть хотя бы один перевод: `definition` или элемент `translations`.
+pub fn has_definition(definition: &Option<String>, translations: usize) -> Result<(), ValidationError> {
+    if definition.is_none() && translations == 0 {
+        return Err(error("definition", "definition or translations is required"));
+    }
+    Ok(())
+}
+
+/// Формы прошедшего/настоящего времени и масдар имеют смысл только у глаголов.
+pub fn verb_forms(part_of_speech: Option<PartOfSpeech>, forms: &[&Option<String>]) -> Result<(), ValidationError> {
+    if part_of_speech != Some(PartOfSpeech::Verb) && forms.iter().any(|form| form.is_some()) {
+        return Err(error("verb_forms", "past, present and masdar are allowed only when part_of_speech is verb"));
+    }
+    Ok(())
+}
+
+/// Тип множественного числа указывается вместе с самой формой.
+pub fn plural_forms(plural: &Option<String>, plural_type: Option<PluralType>) -> Result<(), ValidationError> {
+    if plural_type.is_some() && plural.is_none() {
+        return Err(error("plural_type", "plural_type requires plural"));
+    }
+    Ok(())
+}
+
+/// Ошибки validator превращаются в `{"поле": ["сообщение", ...]}`.
+fn errors_to_json(errors: &ValidationErrors) -> Value {
+    let mut fields = Map::new();
+
+    for (field, kind) in errors.errors() {
+        let value = match kind {
+            ValidationErrorsKind::Field(errors) => Value::Array(
+                errors
+                    .iter()
+                    .map(|e| match &e.message {
+                        Some(message) => Value::String(message.to_string()),
+                        None => Value::String(e.code.to_string()),
+                    })
+                    .collect(),
+            ),
+            ValidationErrorsKind::Struct(errors) => errors_to_json(errors),
+            ValidationErrorsKind::List(items) => Value::Object(
+                items
+                    .iter()
+                    .map(|(index, errors)| (index.to_string(), errors_to_json(errors)))
+                    .collect(),
+            ),
+        };
+        fields.insert(field.to_string(), value);
+    }
+
+    Value::Object(fields)
+}
+
+impl From<ValidationErrors> for AppError {
+    fn from(errors: ValidationErrors) -> Self {
+        AppError::unprocessable("Validation failed", errors_to_json(&errors))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arabic_sentence_allows_punctuation_and_digits() {
+        assert!(arabic_sentence("ذَهَبَ الوَلَدُ إِلَى المَدْرَسَةِ.").is_ok());
+        assert!(arabic_sentence("قَالَ: \"نَعَمْ!\"").is_ok());
+        assert!(arabic_sentence("عِنْدِي 3 كُتُبٍ، وَأَنْتَ؟").is_ok());
+    }
+
+    #[test]
+    fn example_ending_in_period_is_accepted() {
+        use crate::lessons::serializers::RequestExample;
+        use validator::Validate;
+
+        let example = |text: &str| RequestExample {
+            text: text.to_string(),
+            translation: None,
+            source: None,
+            lesson_id: None,
+        };
+
+        assert!(example("ذَهَبَ الوَلَدُ إِلَى المَدْرَسَةِ.").validate().is_ok());
+        assert!(example("The boy went to school.").validate().is_err());
+    }
+
+    #[test]
+    fn arabic_sentence_requires_arabic_letters() {
+        assert!(arabic_sentence("hello world.").is_err());
+        assert!(arabic_sentence("123.").is_err());
+        assert!(arabic_sentence("كتاب book").is_err());
+    }
+
+    #[test]
+    fn web_url_accepts_http_and_https() {
+        assert!(web_url("https://example.com/video.mp4").is_ok());
+        assert!(web_url("http://example.com").is_ok());
+        assert!(web_url("HTTPS://Example.com/watch?v=1").is_ok());
+    }
+
+    #[test]
+    fn web_url_rejects_other_schemes() {
+        assert!(web_url("javascript:alert(1)").is_err());
+        assert!(web_url("JavaScript:alert(1)").is_err());
+        assert!(web_url("data:text/html,<script>alert(1)</script>").is_err());
+        assert!(web_url("file:///etc/passwd").is_err());
+        assert!(web_url("example.com/video").is_err());
+    }
+
+    #[test]
+    fn lesson_with_javascript_video_url_is_rejected() {
+        use crate::lessons::serializers::RequestLesson;
+        use validator::Validate;
+
+        let lesson = |video_url: &str| RequestLesson {
+            title: "Урок".to_string(),
+            text: "كتاب".to_string(),
+            video_url: Some(video_url.to_string()),
+            textbook_id: 1,
+        };
+
+        let errors = lesson("javascript:alert(1)").validate().unwrap_err();
+        assert!(errors.field_errors().contains_key("video_url"));
+        assert!(lesson("https://example.com/lesson.mp4").validate().is_ok());
+    }
+}